@@ -63,6 +63,24 @@ pub async fn main() {
     let data = Activity::insert(&rb, &table).await;
     println!("insert = {}", json!(data));
 
+    let mut table = Activity {
+        id: Some("4".into()),
+        name: Some("4".into()),
+        pc_link: None,
+        h5_link: None,
+        pc_banner_img: None,
+        h5_banner_img: None,
+        sort: None,
+        status: Some(4),
+        remark: None,
+        create_time: None,
+        version: None,
+        delete_flag: None,
+    };
+    //sqlite/postgres use 'returning *', the returned columns write back into table
+    let data = Activity::insert_returning(&rb, &mut table, "id").await;
+    println!("insert_returning = {}, table.id = {:?}", json!(data), table.id);
+
     let tables = vec![
         Activity {
             id: Some("2".into()),
@@ -21,6 +21,7 @@ pub struct Activity {
     pub delete_flag: Option<i32>,
 }
 
+impl_select!(Activity{});
 impl_select!(Activity{select_id_name(id:&str,name:&str) => "`where id = #{id} and name = #{name}`"});
 
 #[tokio::main]
@@ -43,6 +44,13 @@ pub async fn main() {
 
     let data = Activity::select_id_name(&rb, "1", "1").await;
     println!("select_id_name = {}", json!(data));
+
+    let count = Activity::count_by_map(&rb, rbs::to_value!{"status >=": 1}).await;
+    println!("count_by_map = {}", json!(count));
+    let exists = Activity::exists_by_column(&rb, "id", "1").await;
+    println!("exists_by_column = {}", json!(exists));
+    let max_version: rbatis::Result<Option<i64>> = Activity::max_by_map(&rb, "version", rbs::to_value!{}).await;
+    println!("max_by_map = {}", json!(max_version));
 }
 
 
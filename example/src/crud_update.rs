@@ -7,7 +7,7 @@ use serde_json::json;
 use rbatis::impl_update;
 
 /// table
-#[derive(serde::Serialize, serde::Deserialize)]
+#[derive(serde::Serialize, serde::Deserialize, Clone)]
 pub struct Activity {
     pub id: Option<String>,
     pub name: Option<String>,
@@ -23,6 +23,7 @@ pub struct Activity {
     pub delete_flag: Option<i32>,
 }
 
+impl_update!(Activity{});
 impl_update!(Activity{update_by_name(name:&str) => "`where name = #{name}`"});
 
 #[tokio::main]
@@ -83,4 +84,11 @@ pub async fn main() {
 
     let data = Activity::update_by_name(&rb, &table, "2").await;
     println!("update_by_name = {}", json!(data));
+
+    let mut table2 = table.clone();
+    table2.id = Some("3".into());
+    table2.name = None;
+    //one statement: update activity set name = case id when ? then ? else name end,... where id in (?,?)
+    let data = Activity::update_by_column_batch(&rb, &[table, table2], "id", 100).await;
+    println!("update_by_column_batch = {}", json!(data));
 }
@@ -37,6 +37,8 @@ pub async fn main() {
     //let mapper = &table_sync::PGTableMapper{} ;
     //let mapper = &table_sync::MysqlTableMapper{} ;
     //let mapper = &table_sync::MssqlTableMapper{} ;
+    //or override the types of a mapper, instead of the string values as type hints
+    //let mapper = &table_sync::OverrideMapper::new(table_sync::MysqlTableMapper{}).ext("Decimal", "DECIMAL(18,4)").column("remark", "TEXT");
 
     // let table = RBUser{};
     let table = to_value! {
@@ -47,10 +49,16 @@ pub async fn main() {
         "version": "TEXT",
         "delete_flag": "INT8"
     };
-    RBatis::sync(&rb.acquire().await.unwrap(), mapper, &table, "rb_user").await.unwrap();
+    //dry run: print the ddl without executing it
+    let plan = RBatis::sync_plan(&rb.acquire().await.unwrap(), mapper, &table, "rb_user").await.unwrap();
+    for sql in &plan.ddl {
+        println!("plan: {}", sql);
+    }
+    let report = RBatis::sync(&rb.acquire().await.unwrap(), mapper, &table, "rb_user").await.unwrap();
+    println!("{:?}", report);
 
-    //sync table struct
-    RBatis::sync(
+    //sync table struct, the columns of a different type are reported in `mismatched`
+    let report = RBatis::sync(
         &rb.acquire().await.unwrap(),
         mapper,
         &RBUser {
@@ -67,4 +75,9 @@ pub async fn main() {
     )
         .await
         .unwrap();
+    println!("{:?}", report);
+
+    //export the ddl script of the database, it can be checked into the repository and diffed
+    let script = table_sync::export_schema(&rb.acquire().await.unwrap(), mapper, &[]).await.unwrap();
+    println!("{}", script);
 }
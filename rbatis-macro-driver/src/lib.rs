@@ -197,3 +197,43 @@ pub fn rb_html(args: TokenStream, func: TokenStream) -> TokenStream {
 pub fn snake_name(args: TokenStream, func: TokenStream) -> TokenStream {
     macros::snake_name::snake_name(args, func)
 }
+
+/// derive the table info and the crud methods(`crud!`) of a struct,
+/// plus `select_by_id`,`update_by_id`,`delete_by_id` of the pk column(default `id`)
+/// and the column name constants `ActivityColumns::NAME`/`Activity::columns().name`.
+///```log
+/// #[derive(serde::Serialize, serde::Deserialize, rbatis::Table)]
+/// #[table(name = "activity")]
+/// pub struct Activity {
+///     #[column(pk)]
+///     pub id: Option<String>,
+///     #[column(rename = "activity_name")]
+///     pub name: Option<String>,
+///     //generated by database
+///     #[column(insert = false, update = false)]
+///     pub create_time: Option<DateTime>,
+/// }
+///```
+#[proc_macro_derive(Table, attributes(table, column))]
+pub fn table(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as syn::DeriveInput);
+    match macros::table_derive::impl_derive_table(&input) {
+        Ok(stream) => stream.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+/// embed the `V{version}__{name}.sql` files of a dir(relative to `CARGO_MANIFEST_DIR`) at compile time,
+/// the result is `Result<rbatis::migration::Migrations, rbatis::Error>`
+///```log
+/// let migrations = rbatis::embed_migrations!("migrations")?;
+/// migrations.run(&rb).await?;
+///```
+#[proc_macro]
+pub fn embed_migrations(input: TokenStream) -> TokenStream {
+    let dir = parse_macro_input!(input as syn::LitStr);
+    match macros::embed_migrations::impl_embed_migrations(&dir) {
+        Ok(stream) => stream.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
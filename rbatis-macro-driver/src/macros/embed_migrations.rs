@@ -0,0 +1,45 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+use std::path::PathBuf;
+
+/// read the `V{version}__{name}.sql` files of the dir(relative to `CARGO_MANIFEST_DIR`) at compile time,
+/// the sql is embedded by `include_str!`
+pub(crate) fn impl_embed_migrations(dir: &syn::LitStr) -> syn::Result<TokenStream> {
+    let mut path = PathBuf::from(
+        std::env::var("CARGO_MANIFEST_DIR").expect("Failed to read CARGO_MANIFEST_DIR"),
+    );
+    path.push(dir.value());
+    let entries = std::fs::read_dir(&path).map_err(|e| {
+        syn::Error::new(dir.span(), format!("read dir {} fail: {}", path.display(), e))
+    })?;
+    let mut files = vec![];
+    for entry in entries {
+        let file = entry
+            .map_err(|e| syn::Error::new(dir.span(), e.to_string()))?
+            .path();
+        let file_name = file
+            .file_name()
+            .and_then(|v| v.to_str())
+            .unwrap_or_default()
+            .to_string();
+        if let Some((version, name)) = parse_file_name(&file_name) {
+            files.push((version, name, file.display().to_string()));
+        }
+    }
+    files.sort_by_key(|v| v.0);
+    let migrations = files.iter().map(|(version, name, file)| {
+        quote! {
+            rbatis::migration::Migration::new(#version, #name, include_str!(#file))
+        }
+    });
+    Ok(quote! {
+        rbatis::migration::Migrations::new(vec![#(#migrations),*])
+    })
+}
+
+/// same as `Migration::parse_file_name`
+fn parse_file_name(file_name: &str) -> Option<(u64, String)> {
+    let name = file_name.strip_prefix('V')?.strip_suffix(".sql")?;
+    let (version, name) = name.split_once("__")?;
+    Some((version.parse().ok()?, name.to_string()))
+}
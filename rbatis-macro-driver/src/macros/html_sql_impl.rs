@@ -126,7 +126,7 @@ pub(crate) fn impl_macro_html_sql(target_fn: &ItemFn, args: &ParseArgs) -> Token
         call_method = quote! {
              use rbatis::executor::{Executor};
              let r=#rbatis_ident.query(&sql,rb_args).await?;
-             rbatis::decode::decode(r)
+             rbatis::decode::decode_naming(r, #rbatis_ident.rb_ref().naming_strategy)
         };
     } else {
         call_method = quote! {
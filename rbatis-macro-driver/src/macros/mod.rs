@@ -1,4 +1,6 @@
+pub mod embed_migrations;
 pub mod html_sql_impl;
 pub mod py_sql_impl;
 pub mod snake_name;
 pub mod sql_impl;
+pub mod table_derive;
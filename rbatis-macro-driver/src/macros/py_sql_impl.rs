@@ -120,7 +120,7 @@ pub(crate) fn impl_macro_py_sql(target_fn: &ItemFn, args: ParseArgs) -> TokenStr
         call_method = quote! {
              use rbatis::executor::{Executor};
              let r=#rbatis_ident.query(&sql,rb_args).await?;
-             rbatis::decode::decode(r)
+             rbatis::decode::decode_naming(r, #rbatis_ident.rb_ref().naming_strategy)
         };
     } else {
         call_method = quote! {
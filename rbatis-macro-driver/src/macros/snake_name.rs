@@ -34,7 +34,7 @@ pub fn snake_name(args: TokenStream, func: TokenStream) -> TokenStream {
     stream.into()
 }
 
-fn to_snake_name(name: &str) -> String {
+pub(crate) fn to_snake_name(name: &str) -> String {
     let len = name.len();
     let bytes = name.as_bytes();
     let mut new_name = String::with_capacity(name.len());
@@ -66,7 +66,7 @@ pub(crate) fn impl_macro_sql(target_fn: &ItemFn, args: &ParseArgs) -> TokenStrea
     let is_query = is_query(&return_ty.to_string());
     if is_query {
         call_method = quote! {query};
-        decode = quote! { Ok(rbatis::decode::decode(r)?)}
+        decode = quote! { Ok({ use rbatis::executor::RBatisRef; rbatis::decode::decode_naming(r, #rbatis_ident.rb_ref().naming_strategy)? })}
     } else {
         call_method = quote! {exec};
         decode = quote! { Ok(r)}
@@ -0,0 +1,318 @@
+use crate::macros::snake_name::to_snake_name;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, LitBool, LitStr, Token};
+
+/// the column info of a field, parsed from `#[column(...)]` and `#[serde(...)]`
+struct ColumnAttr {
+    ident: syn::Ident,
+    field: String,
+    column: String,
+    pk: bool,
+    insert: bool,
+    update: bool,
+}
+
+pub fn impl_derive_table(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let table_ident = &input.ident;
+    let mut table_name = to_snake_name(&table_ident.to_string());
+    let mut rename_all = None;
+    for attr in &input.attrs {
+        if attr.path().is_ident("serde") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename_all") {
+                    rename_all = Some(parse_rename_all(&meta)?);
+                    return Ok(());
+                }
+                skip_meta_value(&meta)
+            })?;
+        } else if attr.path().is_ident("table") {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    table_name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported table attribute, expected `name`"))
+                }
+            })?;
+        }
+    }
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Err(syn::Error::new_spanned(
+                    table_ident,
+                    "derive(Table) only support struct with named fields",
+                ))
+            }
+        },
+        _ => {
+            return Err(syn::Error::new_spanned(
+                table_ident,
+                "derive(Table) only support struct",
+            ))
+        }
+    };
+    let mut columns = Vec::with_capacity(fields.len());
+    for field in fields {
+        let ident = match &field.ident {
+            Some(v) => v.clone(),
+            None => continue,
+        };
+        let name = ident.to_string().trim_start_matches("r#").to_string();
+        let mut column = ColumnAttr {
+            field: match &rename_all {
+                Some(rule) => apply_rename_all(rule, &name),
+                None => name,
+            },
+            ident,
+            column: String::new(),
+            pk: false,
+            insert: true,
+            update: true,
+        };
+        let mut skip = false;
+        for attr in &field.attrs {
+            if attr.path().is_ident("serde") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("rename") && meta.input.peek(Token![=]) {
+                        column.field = meta.value()?.parse::<LitStr>()?.value();
+                    } else if meta.path.is_ident("skip")
+                        || meta.path.is_ident("skip_serializing")
+                        || meta.path.is_ident("skip_deserializing")
+                    {
+                        skip = true;
+                    }
+                    skip_meta_value(&meta)
+                })?;
+            } else if attr.path().is_ident("column") {
+                attr.parse_nested_meta(|meta| {
+                    if meta.path.is_ident("pk") {
+                        column.pk = true;
+                    } else if meta.path.is_ident("rename") {
+                        column.column = meta.value()?.parse::<LitStr>()?.value();
+                    } else if meta.path.is_ident("insert") {
+                        column.insert = meta.value()?.parse::<LitBool>()?.value;
+                    } else if meta.path.is_ident("update") {
+                        column.update = meta.value()?.parse::<LitBool>()?.value;
+                    } else {
+                        return Err(meta.error(
+                            "unsupported column attribute, expected `pk`,`rename`,`insert`,`update`",
+                        ));
+                    }
+                    Ok(())
+                })?;
+            }
+        }
+        if skip {
+            continue;
+        }
+        if column.column.is_empty() {
+            column.column = column.field.clone();
+        }
+        columns.push(column);
+    }
+    let pks: Vec<&ColumnAttr> = columns.iter().filter(|c| c.pk).collect();
+    if pks.len() > 1 {
+        return Err(syn::Error::new_spanned(
+            table_ident,
+            "derive(Table) only support one #[column(pk)] field",
+        ));
+    }
+    //default pk is the 'id' column
+    let pk = pks
+        .first()
+        .map(|c| c.column.clone())
+        .or_else(|| columns.iter().find(|c| c.column == "id").map(|c| c.column.clone()));
+
+    let select_column = columns
+        .iter()
+        .map(|c| {
+            if c.column == c.field {
+                c.column.clone()
+            } else {
+                format!("{} as {}", c.column, c.field)
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",");
+    let table_columns = columns.iter().map(|c| {
+        let ColumnAttr {
+            ident: _,
+            field,
+            column,
+            pk,
+            insert,
+            update,
+        } = c;
+        quote! {
+            rbatis::crud_traits::TableColumn {
+                field: #field,
+                column: #column,
+                pk: #pk,
+                insert: #insert,
+                update: #update,
+            }
+        }
+    });
+    let mut by_id = quote! {};
+    if let Some(pk) = pk {
+        by_id = quote! {
+            impl #table_ident {
+                pub async fn select_by_id<V: serde::Serialize>(
+                    executor: &dyn rbatis::executor::Executor,
+                    id: V,
+                ) -> std::result::Result<Option<#table_ident>, rbatis::rbdc::Error> {
+                    Ok(<#table_ident>::select_by_column(executor, #pk, id).await?.into_iter().next())
+                }
+
+                /// will skip null column
+                pub async fn update_by_id(
+                    executor: &dyn rbatis::executor::Executor,
+                    table: &#table_ident,
+                ) -> std::result::Result<rbatis::rbdc::db::ExecResult, rbatis::rbdc::Error> {
+                    <#table_ident>::update_by_column(executor, table, #pk).await
+                }
+
+                pub async fn delete_by_id<V: serde::Serialize>(
+                    executor: &dyn rbatis::executor::Executor,
+                    id: V,
+                ) -> std::result::Result<rbatis::rbdc::db::ExecResult, rbatis::rbdc::Error> {
+                    <#table_ident>::delete_by_column(executor, #pk, id).await
+                }
+            }
+        };
+    }
+    let vis = &input.vis;
+    let columns_ident = format_ident!("{}Columns", table_ident);
+    let columns_doc = format!("the column names of [`{}`]", table_ident);
+    let column_fields = columns.iter().map(|c| {
+        let ident = &c.ident;
+        quote! { pub #ident: &'static str }
+    });
+    let column_values = columns.iter().map(|c| {
+        let ident = &c.ident;
+        let column = &c.column;
+        quote! { #ident: #column }
+    });
+    let column_consts = columns.iter().map(|c| {
+        let name = format_ident!(
+            "{}",
+            c.ident.to_string().trim_start_matches("r#").to_uppercase()
+        );
+        let column = &c.column;
+        quote! { pub const #name: &'static str = #column; }
+    });
+    Ok(quote! {
+        #[doc = #columns_doc]
+        #[derive(Debug, Clone, Copy)]
+        #vis struct #columns_ident {
+            #(#column_fields),*
+        }
+
+        impl #columns_ident {
+            #(#column_consts)*
+        }
+
+        impl #table_ident {
+            /// the column names, for example `Self::columns().id`
+            pub const fn columns() -> #columns_ident {
+                #columns_ident {
+                    #(#column_values),*
+                }
+            }
+        }
+
+        impl rbatis::crud_traits::Table for #table_ident {
+            fn table_name() -> &'static str {
+                #table_name
+            }
+
+            fn table_columns() -> &'static [rbatis::crud_traits::TableColumn] {
+                &[#(#table_columns),*]
+            }
+        }
+        rbatis::crud!(#table_ident{}, #table_name, #select_column, <#table_ident as rbatis::crud_traits::Table>::table_columns());
+        #by_id
+    })
+}
+
+/// the rule of `#[serde(rename_all = "...")]`, the serialize and deserialize rules must be the same
+fn parse_rename_all(meta: &syn::meta::ParseNestedMeta) -> syn::Result<String> {
+    let mut rules = vec![];
+    if meta.input.peek(Token![=]) {
+        rules.push(meta.value()?.parse::<LitStr>()?);
+    } else {
+        meta.parse_nested_meta(|meta| {
+            if meta.path.is_ident("serialize") || meta.path.is_ident("deserialize") {
+                rules.push(meta.value()?.parse::<LitStr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("unsupported rename_all, expected `serialize`,`deserialize`"))
+            }
+        })?;
+    }
+    let rule = match rules.first() {
+        Some(v) => v,
+        None => return Err(meta.error("rename_all need a rule")),
+    };
+    if rules.iter().any(|v| v.value() != rule.value()) {
+        return Err(meta.error(
+            "derive(Table) need the same rename_all of serialize and deserialize",
+        ));
+    }
+    const RULES: [&str; 8] = [
+        "lowercase",
+        "UPPERCASE",
+        "PascalCase",
+        "camelCase",
+        "snake_case",
+        "SCREAMING_SNAKE_CASE",
+        "kebab-case",
+        "SCREAMING-KEBAB-CASE",
+    ];
+    if !RULES.contains(&rule.value().as_str()) {
+        return Err(syn::Error::new_spanned(
+            rule,
+            format!("unknown rename_all rule, expected one of {:?}", RULES),
+        ));
+    }
+    Ok(rule.value())
+}
+
+/// the field name renamed by the rule like serde, `create_time` => `createTime`(camelCase)
+fn apply_rename_all(rule: &str, field: &str) -> String {
+    match rule {
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" | "camelCase" => {
+            let mut name = String::with_capacity(field.len());
+            let mut upper = rule == "PascalCase";
+            for c in field.chars() {
+                if c == '_' {
+                    upper = true;
+                } else if upper {
+                    name.push(c.to_ascii_uppercase());
+                    upper = false;
+                } else {
+                    name.push(c);
+                }
+            }
+            name
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.replace('_', "-").to_ascii_uppercase(),
+        _ => field.to_string(),
+    }
+}
+
+/// consume the value of a serde attribute we don't care, for example `default = "..."` or `with(...)`
+fn skip_meta_value(meta: &syn::meta::ParseNestedMeta) -> syn::Result<()> {
+    if meta.input.peek(Token![=]) {
+        meta.value()?.parse::<syn::Expr>()?;
+    } else if meta.input.peek(syn::token::Paren) {
+        let _content;
+        syn::parenthesized!(_content in meta.input);
+    }
+    Ok(())
+}
@@ -6,7 +6,8 @@
 /// pub struct MockTable{
 ///    pub id: Option<String>
 /// }
-/// rbatis::crud!(MockTable{}); //or crud!(MockTable{},"mock_table");
+/// rbatis::crud!(MockTable{}); //or crud!(MockTable{},"mock_table"); or default select columns crud!(MockTable{},"mock_table","id");
+/// //or use #[derive(rbatis::Table)] to declare the table name, pk and column attributes
 ///
 /// //use
 /// async fn test_use(rb:&RBatis) -> Result<(),Error>{
@@ -17,6 +18,7 @@
 ///  let tables = MockTable::select_by_column(rb,"id","1").await;
 ///  let tables = MockTable::select_all(rb).await;
 ///  let tables = MockTable::select_in_column(rb,"id", &vec!["1","2","3"]).await;
+///  let tables = MockTable::select_by_column_cols(rb,&["id"],"id","1").await;
 ///
 ///  let r = MockTable::update_by_column(rb, &table,"id").await;
 ///
@@ -41,6 +43,18 @@ macro_rules! crud {
         $crate::impl_update!($table {}, $table_name);
         $crate::impl_delete!($table {}, $table_name);
     };
+    ($table:ty{},$table_name:expr,$table_column:expr) => {
+        $crate::impl_insert!($table {}, $table_name);
+        $crate::impl_select!($table {}, $table_name, $table_column);
+        $crate::impl_update!($table {}, $table_name);
+        $crate::impl_delete!($table {}, $table_name);
+    };
+    ($table:ty{},$table_name:expr,$table_column:expr,$column_mapping:expr) => {
+        $crate::impl_insert!($table {}, $table_name, $column_mapping);
+        $crate::impl_select!($table {}, $table_name, $table_column);
+        $crate::impl_update!($table {}, $table_name, $column_mapping);
+        $crate::impl_delete!($table {}, $table_name);
+    };
 }
 
 ///PySql: gen sql => INSERT INTO table_name (column1,column2,column3,...) VALUES (value1,value2,value3,...);
@@ -59,6 +73,9 @@ macro_rules! crud {
 ///  let table = MockTable{id: Some("1".to_string())};
 ///  let r = MockTable::insert(rb, &table).await;
 ///  let r = MockTable::insert_batch(rb, std::slice::from_ref(&table),10).await;
+///  //write back generated id
+///  let mut table = MockTable{id: None};
+///  let r = MockTable::insert_returning(rb, &mut table, "id").await;
 ///  Ok(())
 /// }
 /// ```
@@ -69,43 +86,31 @@ macro_rules! impl_insert {
         $crate::impl_insert!($table {}, "");
     };
     ($table:ty{},$table_name:expr) => {
+        $crate::impl_insert!($table {}, $table_name, &[]);
+    };
+    ($table:ty{},$table_name:expr,$column_mapping:expr) => {
         impl $table {
+            /// call the [`TableHooks`]($crate::crud_traits::TableHooks) of the tables if impl
             pub async fn insert_batch(
                 executor: &dyn $crate::executor::Executor,
                 tables: &[$table],
                 batch_size: u64,
             ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
-                pub trait ColumnSet {
-                    /// take `vec![Table{"id":1}]` columns
-                    fn column_sets(&self) -> rbs::Value;
-                }
-                impl ColumnSet for rbs::Value {
-                    fn column_sets(&self) -> rbs::Value {
-                        let len = self.len();
-                        let mut column_set = std::collections::HashSet::with_capacity(len);
-                        if let Some(array) = self.as_array(){
-                            for item in array {
-                             for (k,v) in &item {
-                                if (*v) != rbs::Value::Null{
-                                    column_set.insert(k);
-                                }
-                             }
-                            }
-                        }
-                        let mut columns = rbs::Value::Array(vec![]);
-                        if len > 0 {
-                            let table = &self[0];
-                            let mut column_datas = Vec::with_capacity(table.len());
-                            for (column, _) in table {
-                                if column_set.contains(&column) {
-                                    column_datas.push(column);
-                                }
-                            }
-                            columns = rbs::Value::from(column_datas);
-                        }
-                        columns
-                    }
-                }
+                use $crate::crud_traits::{CallHooks as _, SkipHooks as _};
+                let hooks = $crate::crud_traits::Hooks::<$table>::new();
+                let hooked = (&hooks).before_insert(tables)?;
+                let tables = hooked.as_deref().unwrap_or(tables);
+                let result = <$table>::insert_batch_unhooked(executor, tables, batch_size).await?;
+                (&hooks).after_insert(tables, &result)?;
+                Ok(result)
+            }
+
+            async fn insert_batch_unhooked(
+                executor: &dyn $crate::executor::Executor,
+                tables: &[$table],
+                batch_size: u64,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                use $crate::crud_traits::ColumnSet;
                 #[$crate::py_sql(
                     "`insert into ${table_name} `
                     trim ',':
@@ -126,7 +131,7 @@ macro_rules! impl_insert {
                 )]
                 async fn insert_batch(
                     executor: &dyn $crate::executor::Executor,
-                    tables: &[$table],
+                    tables: &rbs::Value,
                     table_name: &str,
                 ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error>
                 {
@@ -147,16 +152,30 @@ macro_rules! impl_insert {
                     rows_affected: 0,
                     last_insert_id: rbs::Value::Null,
                 };
+                let mut ids = vec![];
                 let ranges = $crate::plugin::Page::<()>::make_ranges(tables.len() as u64, batch_size);
                 for (offset, limit) in ranges {
+                    let mut values = $crate::crud_traits::to_columns(
+                        rbs::value!(&tables[offset as usize..limit as usize]),
+                        $column_mapping,
+                        executor.rb_ref().naming_strategy,
+                        Some($crate::crud_traits::ColumnOp::Insert),
+                    );
+                    let generated_ids = $crate::crud_traits::assign_ids(executor, &table_name, &mut values);
                     let exec_result = insert_batch(
                         executor,
-                        &tables[offset as usize..limit as usize],
+                        &values,
                         table_name.as_str(),
                     )
                     .await?;
                     result.rows_affected += exec_result.rows_affected;
-                    result.last_insert_id = exec_result.last_insert_id;
+                    match generated_ids {
+                        Some(generated_ids) => ids.extend(generated_ids),
+                        None => result.last_insert_id = exec_result.last_insert_id,
+                    }
+                }
+                if !ids.is_empty() {
+                    result.last_insert_id = rbs::Value::Array(ids);
                 }
                 Ok(result)
             }
@@ -167,6 +186,159 @@ macro_rules! impl_insert {
             ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
                 <$table>::insert_batch(executor, std::slice::from_ref(table), 1).await
             }
+
+            /// insert and write the generated columns back into `tables`.
+            ///
+            /// postgres/sqlite use `returning *`, mssql use `output inserted.*`, the returned rows are matched
+            /// by `column`. the rows of a null `column`(generated by the database) are inserted one by one,
+            /// because the order of the returned rows isn't guaranteed.
+            ///
+            /// other drivers(mysql) use `last_insert_id` of the single statement: the ids `last_insert_id`,
+            /// `last_insert_id + 1`... are written back only if `column` of all the rows is null,
+            /// it assumes the `auto_increment_increment` is 1 and the ids are consecutive.
+            ///
+            /// the `last_insert_id` of result is an array of every row's `column` value
+            pub async fn insert_batch_returning(
+                executor: &dyn $crate::executor::Executor,
+                tables: &mut [$table],
+                column: &str,
+                batch_size: u64,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                use $crate::crud_traits::ColumnSet;
+                #[$crate::py_sql(
+                    "`insert into ${table_name} `
+                    trim ',':
+                     bind columns = tables.column_sets():
+                     for idx,table in tables:
+                      if idx == 0:
+                         `(`
+                         trim ',':
+                           for _,v in columns:
+                              ${v},
+                         `)${output} VALUES `
+                      (
+                      trim ',':
+                       for _,v in columns:
+                         #{table[v]},
+                      ),
+                    `${returning}`
+                    "
+                )]
+                async fn insert_batch_returning(
+                    executor: &dyn $crate::executor::Executor,
+                    tables: &rbs::Value,
+                    table_name: &str,
+                    output: &str,
+                    returning: &str,
+                ) -> std::result::Result<Vec<rbs::Value>, $crate::rbdc::Error>
+                {
+                    impled!()
+                }
+                if tables.is_empty() {
+                    return Err($crate::rbdc::Error::from(
+                        "insert can not insert empty array tables!",
+                    ));
+                }
+                use $crate::crud_traits::{CallHooks as _, SkipHooks as _};
+                let hooks = $crate::crud_traits::Hooks::<$table>::new();
+                (&hooks).before_insert_mut(tables)?;
+                #[$crate::snake_name($table)]
+                fn snake_name() {}
+                let mut table_name = $table_name.to_string();
+                if table_name.is_empty() {
+                    table_name = snake_name();
+                }
+                let clause = $crate::crud_traits::returning_clause(executor.driver_type()?);
+                let mut rows_affected = 0;
+                let mut ids = Vec::with_capacity(tables.len());
+                let ranges = $crate::plugin::Page::<()>::make_ranges(tables.len() as u64, batch_size);
+                for (offset, limit) in ranges {
+                    let chunk = &mut tables[offset as usize..limit as usize];
+                    let mut values = Vec::with_capacity(chunk.len());
+                    match clause {
+                        Some((output, returning)) => {
+                            let mut inserts = $crate::crud_traits::to_columns(rbs::value!(&*chunk), $column_mapping, executor.rb_ref().naming_strategy, Some($crate::crud_traits::ColumnOp::Insert));
+                            //the generated ids are written back by the returned rows
+                            $crate::crud_traits::assign_ids(executor, &table_name, &mut inserts);
+                            let keys: Vec<rbs::Value> = match &inserts {
+                                rbs::Value::Array(rows) => rows.iter().map(|v| v[column].clone()).collect(),
+                                _ => vec![],
+                            };
+                            //the order of the returned rows isn't guaranteed, they are matched by the key.
+                            //the rows without the key are inserted one by one
+                            let keyed = chunk.len() > 1 && keys.iter().all(|v| !v.is_null());
+                            let mut rows = vec![];
+                            if keyed || chunk.len() == 1 {
+                                rows = insert_batch_returning(executor, &inserts, table_name.as_str(), output, returning).await?;
+                            } else if let rbs::Value::Array(inserts) = inserts {
+                                for insert in inserts {
+                                    let insert = rbs::Value::Array(vec![insert]);
+                                    rows.extend(insert_batch_returning(executor, &insert, table_name.as_str(), output, returning).await?);
+                                }
+                            }
+                            rows_affected += rows.len() as u64;
+                            for (idx, table) in chunk.iter().enumerate() {
+                                let mut value = $crate::crud_traits::to_columns(rbs::value!(table), $column_mapping, executor.rb_ref().naming_strategy, None);
+                                let row = if keyed {
+                                    $crate::crud_traits::take_row(&mut rows, column, &keys[idx])
+                                } else {
+                                    rows.get_mut(idx).map(std::mem::take)
+                                };
+                                if let Some(row) = row {
+                                    $crate::crud_traits::merge_row(&mut value, row);
+                                }
+                                values.push(value);
+                            }
+                        }
+                        None => {
+                            let exec_result = <$table>::insert_batch_unhooked(executor, chunk, chunk.len() as u64).await?;
+                            rows_affected += exec_result.rows_affected;
+                            for table in chunk.iter() {
+                                values.push($crate::crud_traits::to_columns(rbs::value!(table), $column_mapping, executor.rb_ref().naming_strategy, None));
+                            }
+                            match (executor.rb_ref().get_id_generator(&table_name), &exec_result.last_insert_id) {
+                                //the ids of the id generator
+                                (Some(generator), rbs::Value::Array(ids)) => {
+                                    for (value, id) in values.iter_mut().zip(ids) {
+                                        value[generator.column.as_str()] = id.clone();
+                                    }
+                                }
+                                _ => {
+                                    //`last_insert_id` is the first generated id of the single statement, the next rows take +1.
+                                    //it's right only if the keys of all the rows are generated, the `auto_increment_increment` is 1
+                                    //and the ids of a statement are consecutive(`innodb_autoinc_lock_mode` 0 or 1)
+                                    if let Some(id) = exec_result.last_insert_id.as_u64() {
+                                        if values.iter().all(|v| v[column].is_null()) {
+                                            for (idx, value) in values.iter_mut().enumerate() {
+                                                value[column] = rbs::Value::U64(id + idx as u64);
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    for (table, value) in chunk.iter_mut().zip(values) {
+                        ids.push(value[column].clone());
+                        *table = rbs::from_value($crate::crud_traits::to_fields::<$table>(value, $column_mapping, executor.rb_ref().naming_strategy))?;
+                    }
+                }
+                let result = $crate::rbdc::db::ExecResult {
+                    rows_affected,
+                    last_insert_id: rbs::Value::Array(ids),
+                };
+                (&hooks).after_insert(tables, &result)?;
+                Ok(result)
+            }
+
+            /// insert and write the generated columns back into `table`
+            pub async fn insert_returning(
+                executor: &dyn $crate::executor::Executor,
+                table: &mut $table,
+                column: &str,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                <$table>::insert_batch_returning(executor, std::slice::from_mut(table), column, 1).await
+            }
         }
     };
 }
@@ -182,6 +354,7 @@ macro_rules! impl_insert {
 /// }
 /// /// default
 ///rbatis::impl_select!(MockTable{});
+/// /// or declare the default select columns: rbatis::impl_select!(MockTable{},"mock_table","id");
 ///rbatis::impl_select!(MockTable{select_all_by_id(id:&str,name:&str) => "`where id = #{id} and name = #{name}`"});
 /// /// container result
 ///rbatis::impl_select!(MockTable{select_by_id(id:String) -> Option => "`where id = #{id} limit 1`"});
@@ -193,6 +366,13 @@ macro_rules! impl_insert {
 ///    let r = MockTable::select_all_by_id(rb,"1","xxx").await?;
 ///    let r:Option<MockTable> = MockTable::select_by_id(rb,"1".to_string()).await?;
 ///    let r:Vec<MockTable> = MockTable::select_by_id2(rb,"1".to_string()).await?;
+///    //only select id column
+///    let r:Vec<MockTable> = MockTable::select_by_column_cols(rb,&["id"],"id","1").await?;
+///    let count:u64 = MockTable::count_by_map(rb,rbs::to_value!{"id >": "1"}).await?;
+///    let exists:bool = MockTable::exists_by_column(rb,"id","1").await?;
+///    let max:Option<String> = MockTable::max_by_map(rb,"id",rbs::to_value!{}).await?;
+///    //operators, or groups, order_by and limit
+///    let r:Vec<MockTable> = MockTable::select_by_map(rb,rbs::to_value!{"id >": "1", "id in": ["2","3"], "order_by": "id desc", "limit": 10}).await?;
 ///    Ok(())
 /// }
 /// ```
@@ -203,23 +383,185 @@ macro_rules! impl_select {
         $crate::impl_select!($table{},"");
     };
     ($table:ty{},$table_name:expr) => {
-        $crate::impl_select!($table{select_all() => ""},$table_name);
-        $crate::impl_select!($table{select_by_column<V:serde::Serialize>(column: &str,column_value: V) -> Vec => "` where ${column} = #{column_value}`"},$table_name);
-        $crate::impl_select!($table{select_by_map(condition:rbs::Value) -> Vec =>
-        "` where `
-         trim ' and ': for key,item in condition:
-             ` and ${key} = #{item}`
-        "},$table_name);
+        $crate::impl_select!($table{},$table_name,"*");
+    };
+    ($table:ty{},$table_name:expr,$table_column:expr) => {
+        $crate::impl_select!($table{select_all() => ""},$table_name,$table_column);
+        $crate::impl_select!($table{select_by_column<V:serde::Serialize>(column: &str,column_value: V) -> Vec => "` where ${column} = #{column_value}`"},$table_name,$table_column);
         $crate::impl_select!($table{select_in_column<V:serde::Serialize>(column: &str,column_values: &[V]) -> Vec =>
          "` where ${column} in (`
           trim ',': for _,item in column_values:
              #{item},
-          `)`"},$table_name => { if column_values.is_empty() { return Ok(vec![]); }} );
+          `)`"},$table_name,$table_column => { if column_values.is_empty() { return Ok(vec![]); }} );
+        impl $table {
+            /// select by a condition map, see [`condition_sql`]($crate::crud_traits::condition_sql)
+            /// for the operators, `or` groups, `order_by`, `limit` and `offset`
+            pub async fn select_by_map(
+                executor: &dyn $crate::executor::Executor,
+                condition: rbs::Value,
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let mut table_column = $table_column.to_string();
+                if table_column.is_empty(){
+                    table_column = "*".to_string();
+                }
+                let condition = $crate::crud_traits::condition_sql(&condition, executor.driver_type()?)?;
+                let sql = format!("select {} from {} {}{}", table_column, table_name, condition.where_sql, condition.tail_sql);
+                let values = executor.query(&sql, condition.args).await?;
+                $crate::decode_naming(values, executor.rb_ref().naming_strategy)
+            }
+
+            /// `select count(1) as count from table where ...`, see [`condition_sql`]($crate::crud_traits::condition_sql).
+            /// the `order_by`,`limit`,`offset` of the condition are ignored
+            pub async fn count_by_map(
+                executor: &dyn $crate::executor::Executor,
+                condition: rbs::Value,
+            ) -> std::result::Result<u64, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let condition = $crate::crud_traits::condition_sql(&condition, executor.driver_type()?)?;
+                let sql = format!("select count(1) as count from {} {}", table_name, condition.where_sql);
+                let values = executor.query(&sql, condition.args).await?;
+                $crate::decode(values)
+            }
+
+            /// is there any row `where column = column_value`
+            pub async fn exists_by_column<V:serde::Serialize>(
+                executor: &dyn $crate::executor::Executor,
+                column: &str,
+                column_value: V,
+            ) -> std::result::Result<bool, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let sql = if executor.driver_type()? == "mssql" {
+                    format!("select top 1 1 from {} where {} = ?", table_name, column)
+                } else {
+                    format!("select 1 from {} where {} = ? limit 1", table_name, column)
+                };
+                let rows = executor.query(&sql, vec![rbs::value!(column_value)]).await?;
+                Ok(rows.as_array().map(|v| !v.is_empty()).unwrap_or_default())
+            }
+
+            /// `select func(column) as value from table where ...`, `func` is an aggregate function
+            /// `count`,`sum`,`min`,`max`,`avg` and `column` is an identifier, see [`check_aggregate`]($crate::crud_traits::check_aggregate).
+            /// return `None` if no row matched
+            pub async fn aggregate_by_map<T:serde::de::DeserializeOwned>(
+                executor: &dyn $crate::executor::Executor,
+                func: &str,
+                column: &str,
+                condition: rbs::Value,
+            ) -> std::result::Result<Option<T>, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                $crate::crud_traits::check_aggregate(func, column)?;
+                let condition = $crate::crud_traits::condition_sql(&condition, executor.driver_type()?)?;
+                let sql = format!("select {}({}) as value from {} {}", func, column, table_name, condition.where_sql);
+                let values = executor.query(&sql, condition.args).await?;
+                $crate::decode(values)
+            }
+
+            pub async fn sum_by_map<T:serde::de::DeserializeOwned>(
+                executor: &dyn $crate::executor::Executor,
+                column: &str,
+                condition: rbs::Value,
+            ) -> std::result::Result<Option<T>, $crate::rbdc::Error> {
+                <$table>::aggregate_by_map(executor, "sum", column, condition).await
+            }
+
+            pub async fn min_by_map<T:serde::de::DeserializeOwned>(
+                executor: &dyn $crate::executor::Executor,
+                column: &str,
+                condition: rbs::Value,
+            ) -> std::result::Result<Option<T>, $crate::rbdc::Error> {
+                <$table>::aggregate_by_map(executor, "min", column, condition).await
+            }
+
+            pub async fn max_by_map<T:serde::de::DeserializeOwned>(
+                executor: &dyn $crate::executor::Executor,
+                column: &str,
+                condition: rbs::Value,
+            ) -> std::result::Result<Option<T>, $crate::rbdc::Error> {
+                <$table>::aggregate_by_map(executor, "max", column, condition).await
+            }
+
+            /// select only `columns`(empty means the table default columns),
+            /// the fields not selected decode as `None`, so a not `Option` field must be selected
+            /// or have `#[serde(default)]`
+            pub async fn select_by_column_cols<V:serde::Serialize>(
+                executor: &dyn $crate::executor::Executor,
+                columns: &[&str],
+                column: &str,
+                column_value: V,
+            ) -> std::result::Result<Vec<$table>, $crate::rbdc::Error> {
+                let rows = <$table>::select_by_column_as::<rbs::Value, V>(executor, columns, column, column_value).await?;
+                $crate::decode_naming(rbs::Value::Array(rows), executor.rb_ref().naming_strategy).map_err(|e| {
+                    if columns.is_empty() {
+                        return e;
+                    }
+                    $crate::rbdc::Error::from(format!(
+                        "{}, the field not in the columns {:?} must be Option or #[serde(default)]",
+                        e, columns
+                    ))
+                })
+            }
+
+            /// select only `columns`(empty means the table default columns) and decode into `T`,
+            /// for example a dto have less fields than the table
+            pub async fn select_by_column_as<T:serde::de::DeserializeOwned, V:serde::Serialize>(
+                executor: &dyn $crate::executor::Executor,
+                columns: &[&str],
+                column: &str,
+                column_value: V,
+            ) -> std::result::Result<Vec<T>, $crate::rbdc::Error> {
+                #[$crate::py_sql("`select ${table_column} from ${table_name} where ${column} = #{column_value}`")]
+                async fn select_by_column_as<V:serde::Serialize>(
+                    executor: &dyn $crate::executor::Executor,
+                    table_column: &str,
+                    table_name: &str,
+                    column: &str,
+                    column_value: V,
+                ) -> std::result::Result<rbs::Value, $crate::rbdc::Error> {
+                    impled!()
+                }
+                let mut table_column = columns.join(",");
+                if table_column.is_empty() {
+                    table_column = $table_column.to_string();
+                }
+                if table_column.is_empty() {
+                    table_column = "*".to_string();
+                }
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let values = select_by_column_as(executor, &table_column, &table_name, column, column_value).await?;
+                $crate::decode_naming(values, executor.rb_ref().naming_strategy)
+            }
+        }
     };
-    ($table:ty{$fn_name:ident $(< $($gkey:ident:$gtype:path $(,)?)* >)? ($($param_key:ident:$param_type:ty $(,)?)*) => $sql:expr}$(,$table_name:expr)?) => {
-        $crate::impl_select!($table{$fn_name$(<$($gkey:$gtype,)*>)?($($param_key:$param_type,)*) ->Vec => $sql}$(,$table_name)?);
+    ($table:ty{$fn_name:ident $(< $($gkey:ident:$gtype:path $(,)?)* >)? ($($param_key:ident:$param_type:ty $(,)?)*) => $sql:expr}$(,$table_name:expr $(,$table_column:expr)?)?) => {
+        $crate::impl_select!($table{$fn_name$(<$($gkey:$gtype,)*>)?($($param_key:$param_type,)*) ->Vec => $sql}$(,$table_name $(,$table_column)?)?);
     };
-    ($table:ty{$fn_name:ident $(< $($gkey:ident:$gtype:path $(,)?)* >)? ($($param_key:ident:$param_type:ty $(,)?)*) -> $container:tt => $sql:expr}$(,$table_name:expr)?  $( => $cond:expr)? ) => {
+    ($table:ty{$fn_name:ident $(< $($gkey:ident:$gtype:path $(,)?)* >)? ($($param_key:ident:$param_type:ty $(,)?)*) -> $container:tt => $sql:expr}$(,$table_name:expr $(,$table_column:expr)?)?  $( => $cond:expr)? ) => {
         impl $table{
             pub async fn $fn_name $(<$($gkey:$gtype,)*>)? (executor: &dyn  $crate::executor::Executor,$($param_key:$param_type,)*) -> std::result::Result<$container<$table>,$crate::rbdc::Error>
             {
@@ -230,12 +572,15 @@ macro_rules! impl_select {
 
                      let mut table_column = "*".to_string();
                      let mut table_name = String::new();
-                     $(table_name = $table_name.to_string();)?
+                     $(table_name = $table_name.to_string(); $(table_column = $table_column.to_string();)?)?
                      #[$crate::snake_name($table)]
                      fn snake_name(){}
                      if table_name.is_empty(){
                          table_name = snake_name();
                      }
+                     if table_column.is_empty(){
+                         table_column = "*".to_string();
+                     }
                      $fn_name(executor,&table_column,&table_name,$($param_key ,)*).await
             }
         }
@@ -266,15 +611,46 @@ macro_rules! impl_update {
         );
     };
     ($table:ty{},$table_name:expr) => {
-        $crate::impl_update!($table{update_by_map(condition:rbs::Value, skip_null: bool) =>
-        "` where `
-         trim ' and ': for key,item in condition:
-             ` and ${key} = #{item}`
-        "
-        },$table_name);
-
-        $crate::impl_update!($table{update_by_column_value(column: &str, column_value: &rbs::Value, skip_null: bool) => "`where ${column} = #{column_value}`"},$table_name);
+        $crate::impl_update!($table{},$table_name,&[]);
+    };
+    ($table:ty{},$table_name:expr,$column_mapping:expr) => {
+        $crate::impl_update!($table{update_by_column_value(column: &str, column_value: &rbs::Value, skip_null: bool) => "`where ${column} = #{column_value}`"},$table_name,$column_mapping);
         impl $table {
+            /// update by a condition map, see [`condition_sql`]($crate::crud_traits::condition_sql).
+            /// the condition can't be empty and can't have `order_by`,`limit`,`offset`
+            pub async fn update_by_map(
+                executor: &dyn $crate::executor::Executor,
+                table: &$table,
+                condition: rbs::Value,
+                skip_null: bool,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let condition = $crate::crud_traits::condition_sql(&condition, executor.driver_type()?)?;
+                if condition.where_sql.is_empty() || !condition.tail_sql.is_empty() {
+                    return Err($crate::rbdc::Error::from("update_by_map condition can't be empty or have order_by/limit/offset!"));
+                }
+                use $crate::crud_traits::{CallHooks as _, SkipHooks as _};
+                let hooks = $crate::crud_traits::Hooks::<$table>::new();
+                let hooked = (&hooks).before_update(table)?;
+                let table = hooked.as_ref().unwrap_or(table);
+                let mut args = Vec::with_capacity(condition.args.len());
+                let values = $crate::crud_traits::to_columns(rbs::value!(table), $column_mapping, executor.rb_ref().naming_strategy, Some($crate::crud_traits::ColumnOp::Update));
+                let sets = $crate::crud_traits::set_sql(&values, skip_null, &mut args);
+                if sets.is_empty() {
+                    return Ok($crate::rbdc::db::ExecResult::default());
+                }
+                args.extend(condition.args);
+                let sql = format!("update {} set {} {}", table_name, sets, condition.where_sql);
+                let result = executor.exec(&sql, args).await?;
+                (&hooks).after_update(table, &result)?;
+                Ok(result)
+            }
+
             ///  will skip null column
             pub async fn update_by_column(
                 executor: &dyn $crate::executor::Executor,
@@ -298,11 +674,13 @@ macro_rules! impl_update {
                 table: &$table,
                 column: &str,
                 skip_null: bool) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error>{
-                let columns = rbs::to_value!(table);
+                let columns = $crate::crud_traits::to_columns(rbs::value!(table), $column_mapping, executor.rb_ref().naming_strategy, None);
                 let column_value = &columns[column];
                 <$table>::update_by_column_value(executor,table,column,column_value,skip_null).await
             }
 
+            /// every batch is one statement:
+            /// `update table set c1 = case column when ? then ? ... else c1 end,... where column in (?,...)`
             pub async fn update_by_column_batch_skip(
                 executor: &dyn $crate::executor::Executor,
                 tables: &[$table],
@@ -310,13 +688,77 @@ macro_rules! impl_update {
                 batch_size: u64,
                 skip_null: bool
             ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                use $crate::crud_traits::ColumnSet;
+                #[$crate::py_sql(
+                    "`update ${table_name} set `
+                     trim ',':
+                       for _,c in columns:
+                         `${c} = case ${column}`
+                         for idx,t in tables:
+                           if skip_null == true && t[c] == null:
+                              continue:
+                           ` when #{keys[idx]} then #{t[c]}`
+                         ` else ${c} end,`
+                     ` where ${column} in (`
+                     trim ',':
+                       for _,k in keys:
+                         #{k},
+                     `)`"
+                )]
+                async fn update_by_column_batch_skip(
+                    executor: &dyn $crate::executor::Executor,
+                    table_name: &str,
+                    tables: &rbs::Value,
+                    columns: &rbs::Value,
+                    column: &str,
+                    keys: &rbs::Value,
+                    skip_null: bool,
+                ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                    impled!()
+                }
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                use $crate::crud_traits::{CallHooks as _, SkipHooks as _};
+                let hooks = $crate::crud_traits::Hooks::<$table>::new();
+                let mut hooked = Vec::new();
+                for table in tables {
+                    if let Some(table) = (&hooks).before_update(table)? {
+                        hooked.push(table);
+                    }
+                }
+                let tables = if hooked.is_empty() { tables } else { hooked.as_slice() };
                 let mut rows_affected = 0;
                 let ranges = $crate::plugin::Page::<()>::make_ranges(tables.len() as u64, batch_size);
                 for (offset, limit) in ranges {
-                    //todo better way impl batch?
-                    for table in &tables[offset as usize..limit as usize]{
-                       rows_affected += <$table>::update_by_column_skip(executor,table,column,skip_null).await?.rows_affected;
+                    let values = rbs::value!(&tables[offset as usize..limit as usize]);
+                    let mut keys = Vec::with_capacity(limit as usize - offset as usize);
+                    for (_, table) in &$crate::crud_traits::to_columns(values.clone(), $column_mapping, executor.rb_ref().naming_strategy, None) {
+                        keys.push(table[column].clone());
+                    }
+                    let values = $crate::crud_traits::to_columns(values, $column_mapping, executor.rb_ref().naming_strategy, Some($crate::crud_traits::ColumnOp::Update));
+                    let mut columns = vec![];
+                    if skip_null {
+                        if let rbs::Value::Array(arr) = values.column_sets() {
+                            columns = arr;
+                        }
+                    } else {
+                        for (k, _) in &values[0] {
+                            columns.push(k);
+                        }
+                    }
+                    columns.retain(|c| c.as_str() != Some(column));
+                    if columns.is_empty() {
+                        continue;
+                    }
+                    let result = update_by_column_batch_skip(executor, &table_name, &values, &rbs::Value::Array(columns), column, &rbs::Value::Array(keys), skip_null).await?;
+                    for table in &tables[offset as usize..limit as usize] {
+                        (&hooks).after_update(table, &result)?;
                     }
+                    rows_affected += result.rows_affected;
                 }
                 Ok($crate::rbdc::db::ExecResult{
                     rows_affected:rows_affected,
@@ -325,7 +767,7 @@ macro_rules! impl_update {
             }
         }
     };
-    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $sql_where:expr}$(,$table_name:expr)?) => {
+    ($table:ty{$fn_name:ident($($param_key:ident:$param_type:ty$(,)?)*) => $sql_where:expr}$(,$table_name:expr $(,$column_mapping:expr)?)?) => {
         impl $table {
             pub async fn $fn_name(
                 executor: &dyn $crate::executor::Executor,
@@ -354,14 +796,21 @@ macro_rules! impl_update {
                       impled!()
                   }
                   let mut table_name = String::new();
-                  $(table_name = $table_name.to_string();)?
+                  let mut column_mapping: &[$crate::crud_traits::TableColumn] = &[];
+                  $(table_name = $table_name.to_string(); $(column_mapping = $column_mapping;)?)?
                   #[$crate::snake_name($table)]
                   fn snake_name(){}
                   if table_name.is_empty(){
                          table_name = snake_name();
                   }
-                  let table = rbs::to_value!(table);
-                  $fn_name(executor, table_name, &table, true, $($param_key,)*).await
+                  use $crate::crud_traits::{CallHooks as _, SkipHooks as _};
+                  let hooks = $crate::crud_traits::Hooks::<$table>::new();
+                  let hooked = (&hooks).before_update(table)?;
+                  let table = hooked.as_ref().unwrap_or(table);
+                  let values = $crate::crud_traits::to_columns(rbs::value!(table), column_mapping, executor.rb_ref().naming_strategy, Some($crate::crud_traits::ColumnOp::Update));
+                  let result = $fn_name(executor, table_name, &values, true, $($param_key,)*).await?;
+                  (&hooks).after_update(table, &result)?;
+                  Ok(result)
             }
         }
     };
@@ -392,11 +841,6 @@ macro_rules! impl_delete {
     };
     ($table:ty{},$table_name:expr) => {
         $crate::impl_delete!($table{ delete_by_column<V:serde::Serialize>(column:&str,column_value: V) => "`where ${column} = #{column_value}`"},$table_name);
-        $crate::impl_delete!($table{ delete_by_map(condition:rbs::Value) =>
-        "` where `
-         trim ' and ': for key,item in condition:
-             ` and ${key} = #{item}`
-        "},$table_name);
         $crate::impl_delete!($table {delete_in_column<V:serde::Serialize>(column:&str,column_values: &[V]) =>
         "`where ${column} in (`
           trim ',': for _,item in column_values:
@@ -404,6 +848,32 @@ macro_rules! impl_delete {
           `)`"},$table_name => { if column_values.is_empty() { return Ok($crate::rbdc::db::ExecResult::default()); }} );
 
         impl $table {
+            /// delete by a condition map, see [`condition_sql`]($crate::crud_traits::condition_sql).
+            /// the condition can't be empty and can't have `order_by`,`limit`,`offset`
+            pub async fn delete_by_map(
+                executor: &dyn $crate::executor::Executor,
+                condition: rbs::Value,
+            ) -> std::result::Result<$crate::rbdc::db::ExecResult, $crate::rbdc::Error> {
+                let mut table_name = $table_name.to_string();
+                #[$crate::snake_name($table)]
+                fn snake_name(){}
+                if table_name.is_empty(){
+                    table_name = snake_name();
+                }
+                let condition_value = condition;
+                let condition = $crate::crud_traits::condition_sql(&condition_value, executor.driver_type()?)?;
+                if condition.where_sql.is_empty() || !condition.tail_sql.is_empty() {
+                    return Err($crate::rbdc::Error::from("delete_by_map condition can't be empty or have order_by/limit/offset!"));
+                }
+                use $crate::crud_traits::{CallHooks as _, SkipHooks as _};
+                let hooks = $crate::crud_traits::Hooks::<$table>::new();
+                let hooked = (&hooks).before_delete(|| condition_value.clone())?;
+                let sql = format!("delete from {} {}", table_name, condition.where_sql);
+                let result = executor.exec(&sql, condition.args).await?;
+                (&hooks).after_delete(hooked, &result)?;
+                Ok(result)
+            }
+
             pub async fn delete_by_column_batch<V:serde::Serialize>(
                 executor: &dyn $crate::executor::Executor,
                 column: &str,
@@ -447,7 +917,16 @@ macro_rules! impl_delete {
                 if table_name.is_empty(){
                          table_name = snake_name();
                 }
-                $fn_name(executor, table_name, $($param_key,)*).await
+                use $crate::crud_traits::{CallHooks as _, SkipHooks as _};
+                let hooks = $crate::crud_traits::Hooks::<$table>::new();
+                let hooked = (&hooks).before_delete(|| {
+                    let mut condition = rbs::value::map::ValueMap::new();
+                    $(condition.insert(stringify!($param_key).into(), rbs::value!(&$param_key));)*
+                    rbs::Value::Map(condition)
+                })?;
+                let result = $fn_name(executor, table_name, $($param_key,)*).await?;
+                (&hooks).after_delete(hooked, &result)?;
+                Ok(result)
             }
         }
     };
@@ -575,7 +1054,7 @@ macro_rules! htmlsql_select_page {
              }
              let mut page = $crate::plugin::Page::<$table>::new(page_request.page_no(), page_request.page_size(), total,vec![]);
              let records_value = $fn_name(executor, false, page_request.offset(), page_request.page_size(), $(&$param_key,)*).await?;
-             page.records = rbs::from_value(records_value)?;
+             page.records = $crate::decode_naming(records_value, executor.rb_ref().naming_strategy)?;
              Ok(page)
          }
     }
@@ -644,7 +1123,7 @@ macro_rules! pysql_select_page {
               }
               let mut page = $crate::plugin::Page::<$table>::new(page_request.page_no(), page_request.page_size(), total,vec![]);
               let records_value = $fn_name(executor, false, page_request.offset(), page_request.page_size(), $(&$param_key,)*).await?;
-              page.records = rbs::from_value(records_value)?;
+              page.records = $crate::decode_naming(records_value, executor.rb_ref().naming_strategy)?;
               Ok(page)
          }
     }
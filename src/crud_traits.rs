@@ -0,0 +1,689 @@
+use crate::decode::NamingStrategy;
+use crate::executor::Executor;
+use crate::Error;
+use rbdc::db::ExecResult;
+use rbs::Value;
+use serde::de::DeserializeOwned;
+use std::marker::PhantomData;
+
+/// take `vec![Table{"id":1}]` columns
+pub trait ColumnSet {
+    /// the not null columns of all rows, ordered by the first row
+    fn column_sets(&self) -> Value;
+}
+
+impl ColumnSet for Value {
+    fn column_sets(&self) -> Value {
+        let len = self.len();
+        let mut column_set = std::collections::HashSet::with_capacity(len);
+        if let Some(array) = self.as_array() {
+            for item in array {
+                for (k, v) in item {
+                    if (*v) != Value::Null {
+                        column_set.insert(k);
+                    }
+                }
+            }
+        }
+        let mut columns = Value::Array(vec![]);
+        if len > 0 {
+            let table = &self[0];
+            let mut column_datas = Vec::with_capacity(table.len());
+            for (column, _) in table {
+                if column_set.contains(&column) {
+                    column_datas.push(column);
+                }
+            }
+            columns = Value::from(column_datas);
+        }
+        columns
+    }
+}
+
+/// the sql clauses used by `insert_returning` for a driver type.
+/// return `(output, returning)`, `output` is placed before `VALUES`, `returning` at the end.
+/// the driver not support both (for example mysql) will return `None`
+pub fn returning_clause(driver_type: &str) -> Option<(&'static str, &'static str)> {
+    match driver_type {
+        "postgres" | "sqlite" => Some(("", " returning *")),
+        "mssql" => Some((" output inserted.*", "")),
+        _ => None,
+    }
+}
+
+/// write the returned row columns back into the table value, a column is matched
+/// case insensitively if the table has no same key(the databases may return `USERID` of `userId`)
+pub fn merge_row(table: &mut Value, row: Value) {
+    if let (Value::Map(table), Value::Map(row)) = (table, row) {
+        for (k, v) in row {
+            if table.0.contains_key(&k) {
+                table.insert(k, v);
+                continue;
+            }
+            let same = k.as_str().and_then(|column| {
+                (&mut *table).into_iter().find(|(key, _)| {
+                    key.as_str()
+                        .map(|key| key.eq_ignore_ascii_case(column))
+                        .unwrap_or(false)
+                })
+            });
+            match same {
+                Some((_, value)) => *value = v,
+                None => {
+                    table.insert(k, v);
+                }
+            }
+        }
+    }
+}
+
+/// take the returned row of the `column` value `key` out of `rows`, the column is matched case insensitively
+/// and the integers of the different types(`I32(1)`,`I64(1)`,`U64(1)`) are the same key
+pub fn take_row(rows: &mut Vec<Value>, column: &str, key: &Value) -> Option<Value> {
+    let key = crate::decode::group_key(key);
+    let idx = rows.iter().position(|row| match row {
+        Value::Map(row) => row.into_iter().any(|(k, v)| {
+            k.as_str()
+                .map(|k| k.eq_ignore_ascii_case(column))
+                .unwrap_or(false)
+                && crate::decode::group_key(v) == key
+        }),
+        _ => false,
+    })?;
+    Some(rows.remove(idx))
+}
+
+/// assign the empty(`null` or `""`) id column of the rows(an array of map) by the
+/// [`id generator`](crate::RBatis::set_id_generator) of the table, return the id column of every row
+/// (the generated and the not empty ids), or `None` if the table has no id generator
+pub fn assign_ids(executor: &dyn Executor, table_name: &str, rows: &mut Value) -> Option<Vec<Value>> {
+    let generator = executor.rb_ref().get_id_generator(table_name)?;
+    let mut ids = vec![];
+    if let Value::Array(rows) = rows {
+        for row in rows {
+            let v = &row[generator.column.as_str()];
+            if !(v.is_null() || v.as_str() == Some("")) {
+                ids.push(v.clone());
+                continue;
+            }
+            if let Value::Map(row) = row {
+                let id = generator.generator.generate_id();
+                row.insert(Value::String(generator.column.clone()), id.clone());
+                ids.push(id);
+            }
+        }
+    }
+    Some(ids)
+}
+
+/// the aggregate functions of `aggregate_by_map`
+pub const AGGREGATE_FUNCS: [&str; 5] = ["count", "sum", "min", "max", "avg"];
+
+/// check the `func(column)` of `aggregate_by_map`, they are not the sql args.
+/// `func` is one of [`AGGREGATE_FUNCS`](case insensitive), `column` is an identifier(the letters, digits and `_`,
+/// not start with a digit, maybe qualified as `t.column`) or `*` of `count`
+pub fn check_aggregate(func: &str, column: &str) -> Result<(), Error> {
+    if !AGGREGATE_FUNCS.iter().any(|v| v.eq_ignore_ascii_case(func)) {
+        return Err(Error::from(format!(
+            "aggregate function '{}' not in {:?}",
+            func, AGGREGATE_FUNCS
+        )));
+    }
+    if column == "*" && func.eq_ignore_ascii_case("count") {
+        return Ok(());
+    }
+    let identifier = column.split('.').all(|part| {
+        part.chars().next().map(|c| !c.is_ascii_digit()).unwrap_or(false)
+            && part.chars().all(|c| c.is_alphanumeric() || c == '_')
+    });
+    if !identifier {
+        return Err(Error::from(format!(
+            "aggregate column '{}' is not an identifier",
+            column
+        )));
+    }
+    Ok(())
+}
+
+/// the sql and args built from a condition map, see [`condition_sql`]
+#[derive(Debug, Default, Clone)]
+pub struct ConditionSql {
+    /// ` where ...`, empty if the condition is empty
+    pub where_sql: String,
+    /// ` order by ... limit ...`, empty if not set
+    pub tail_sql: String,
+    pub args: Vec<Value>,
+}
+
+/// the operators can be used as the key suffix of a condition map.
+/// the longer operator must be matched first, for example `>=` before `>`
+const OPERATORS: [&str; 15] = [
+    "is not null",
+    "is null",
+    "not between",
+    "between",
+    "not like",
+    "like",
+    "not in",
+    "in",
+    ">=",
+    "<=",
+    "!=",
+    "<>",
+    ">",
+    "<",
+    "=",
+];
+
+/// build the sql of a condition map, used by `select_by_map`,`update_by_map`,`delete_by_map`.
+///
+/// * `"column": v` => `column = ?`
+/// * `"column op": v` op is one of `= != <> > >= < <= like not like` => `column op ?`
+/// * `"column in": [a,b]` or `"column not in": [a,b]` => `column in (?,?)`
+/// * `"column between": [a,b]` or `"column not between": [a,b]` => `column between ? and ?`
+/// * `"column is null": ()` or `"column is not null": ()`
+/// * `"or": [{..},{..}]` => `(.. or ..)`, every map of the array is an `and` group
+/// * `"or": {..}` => `(.. or ..)`, every entry of the map is joined by `or`
+/// * `"order_by": "id desc"` or `"order_by": ["id desc","name"]`
+/// * `"limit": 10`,`"offset": 10`
+///
+/// for example:
+/// ```rust
+/// use rbatis::crud_traits::condition_sql;
+/// let condition = rbs::to_value!{
+///     "status": 1,
+///     "age >=": 18,
+///     "name like": "%a%",
+///     "or": [rbs::to_value!{"id in": [1,2]}, rbs::to_value!{"delete_flag is null": ()}],
+///     "order_by": "id desc",
+///     "limit": 10,
+/// };
+/// let sql = condition_sql(&condition, "mysql").unwrap();
+/// assert_eq!(sql.where_sql, " where status = ? and age >= ? and name like ? and (id in (?,?) or delete_flag is null)");
+/// assert_eq!(sql.tail_sql, " order by id desc limit 10");
+/// ```
+pub fn condition_sql(condition: &Value, driver_type: &str) -> Result<ConditionSql, Error> {
+    let mut result = ConditionSql::default();
+    let map = match condition {
+        Value::Map(m) => m,
+        Value::Null => return Ok(result),
+        _ => return Err(Error::from("condition must be a map!")),
+    };
+    let mut order_by = vec![];
+    let mut limit = None;
+    let mut offset = None;
+    let mut items = vec![];
+    for (k, v) in map {
+        let key = k.as_str().unwrap_or_default().trim();
+        match key {
+            "order_by" => match v {
+                Value::Array(arr) => {
+                    for x in arr {
+                        order_by.push(x.as_str().unwrap_or_default().to_string());
+                    }
+                }
+                _ => order_by.push(v.as_str().unwrap_or_default().to_string()),
+            },
+            "limit" => {
+                limit = Some(
+                    v.as_u64()
+                        .ok_or_else(|| Error::from("condition limit must be a number!"))?,
+                );
+            }
+            "offset" => {
+                offset = Some(
+                    v.as_u64()
+                        .ok_or_else(|| Error::from("condition offset must be a number!"))?,
+                );
+            }
+            _ => {
+                items.push(item_sql(key, v, &mut result.args)?);
+            }
+        }
+    }
+    if !items.is_empty() {
+        result.where_sql = format!(" where {}", items.join(" and "));
+    }
+    order_by.retain(|v| !v.is_empty());
+    if !order_by.is_empty() {
+        result.tail_sql.push_str(" order by ");
+        result.tail_sql.push_str(&order_by.join(","));
+    }
+    result
+        .tail_sql
+        .push_str(&limit_sql(!order_by.is_empty(), limit, offset, driver_type));
+    Ok(result)
+}
+
+/// the ` limit n offset m` of a driver type, mssql use ` offset m rows fetch next n rows only`
+/// which must have an `order by`
+pub fn limit_sql(
+    has_order_by: bool,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    driver_type: &str,
+) -> String {
+    let mut sql = String::new();
+    if limit.is_none() && offset.is_none() {
+        return sql;
+    }
+    if driver_type == "mssql" {
+        if !has_order_by {
+            sql.push_str(" order by (select null)");
+        }
+        sql.push_str(&format!(" offset {} rows", offset.unwrap_or_default()));
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" fetch next {} rows only", limit));
+        }
+    } else {
+        //sqlite,mysql,postgres all support 'limit n offset m'
+        sql.push_str(&format!(" limit {}", limit.unwrap_or(i64::MAX as u64)));
+        if let Some(offset) = offset {
+            sql.push_str(&format!(" offset {}", offset));
+        }
+    }
+    sql
+}
+
+/// build `a = ? and b = ?` of an `and` group
+fn group_sql(group: &Value, args: &mut Vec<Value>) -> Result<String, Error> {
+    let map = group
+        .as_map()
+        .ok_or_else(|| Error::from("condition or group must be a map!"))?;
+    let mut items = Vec::with_capacity(map.len());
+    for (k, v) in map {
+        items.push(item_sql(k.as_str().unwrap_or_default().trim(), v, args)?);
+    }
+    if items.len() > 1 {
+        Ok(format!("({})", items.join(" and ")))
+    } else {
+        Ok(items.join(""))
+    }
+}
+
+fn item_sql(key: &str, v: &Value, args: &mut Vec<Value>) -> Result<String, Error> {
+    if key == "or" {
+        let mut items = vec![];
+        match v {
+            Value::Array(arr) => {
+                for group in arr {
+                    items.push(group_sql(group, args)?);
+                }
+            }
+            Value::Map(m) => {
+                for (k, v) in m {
+                    items.push(item_sql(k.as_str().unwrap_or_default().trim(), v, args)?);
+                }
+            }
+            _ => return Err(Error::from("condition 'or' must be an array or map!")),
+        }
+        items.retain(|v| !v.is_empty());
+        return Ok(format!("({})", items.join(" or ")));
+    }
+    let (column, op) = split_operator(key);
+    if column.is_empty() {
+        return Err(Error::from(format!("condition key '{}' have no column!", key)));
+    }
+    match op {
+        "is null" | "is not null" => Ok(format!("{} {}", column, op)),
+        "in" | "not in" => {
+            let arr = v
+                .as_array()
+                .ok_or_else(|| Error::from(format!("condition '{}' must be an array!", key)))?;
+            if arr.is_empty() {
+                //`in ()` is a syntax error
+                return Ok(if op == "in" { "1 = 0" } else { "1 = 1" }.to_string());
+            }
+            let mut sql = format!("{} {} (", column, op);
+            for (idx, x) in arr.iter().enumerate() {
+                if idx != 0 {
+                    sql.push(',');
+                }
+                sql.push('?');
+                args.push(x.clone());
+            }
+            sql.push(')');
+            Ok(sql)
+        }
+        "between" | "not between" => match v.as_array() {
+            Some(arr) if arr.len() == 2 => {
+                args.push(arr[0].clone());
+                args.push(arr[1].clone());
+                Ok(format!("{} {} ? and ?", column, op))
+            }
+            _ => Err(Error::from(format!(
+                "condition '{}' must be an array of 2 values!",
+                key
+            ))),
+        },
+        _ => {
+            args.push(v.clone());
+            Ok(format!("{} {} ?", column, op))
+        }
+    }
+}
+
+/// split `"age >="` into `("age", ">=")`, the default operator is `=`
+fn split_operator(key: &str) -> (&str, &str) {
+    for op in OPERATORS {
+        let split = key.len().saturating_sub(op.len());
+        if key.len() >= op.len()
+            && key.is_char_boundary(split)
+            && key[split..].eq_ignore_ascii_case(op)
+        {
+            let column = &key[..split];
+            let is_word = op.chars().next().unwrap_or_default().is_ascii_alphabetic();
+            //`like` must be split from column by space, but `age>=` is ok
+            if is_word && !column.ends_with(' ') {
+                continue;
+            }
+            return (column.trim(), op);
+        }
+    }
+    (key, "=")
+}
+
+/// build `a=?,b=?` of the update sql, `skip_null` will skip the null value column
+pub fn set_sql(table: &Value, skip_null: bool, args: &mut Vec<Value>) -> String {
+    let mut sql = String::new();
+    for (k, v) in table {
+        if skip_null && v == &Value::Null {
+            continue;
+        }
+        if !sql.is_empty() {
+            sql.push(',');
+        }
+        sql.push_str(k.as_str().unwrap_or_default());
+        sql.push_str("=?");
+        args.push(v.clone());
+    }
+    sql
+}
+
+/// a column of the table struct, generated by `#[derive(rbatis::Table)]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableColumn {
+    /// the serde field name of the struct
+    pub field: &'static str,
+    /// the column name of the table
+    pub column: &'static str,
+    pub pk: bool,
+    /// write the column on insert
+    pub insert: bool,
+    /// write the column on update
+    pub update: bool,
+}
+
+/// the table info of a struct, impl by `#[derive(rbatis::Table)]`
+pub trait Table {
+    fn table_name() -> &'static str;
+
+    fn table_columns() -> &'static [TableColumn];
+
+    /// the column of `#[column(pk)]`
+    fn pk_column() -> Option<&'static str> {
+        Self::table_columns()
+            .iter()
+            .find(|c| c.pk)
+            .map(|c| c.column)
+    }
+}
+
+/// the sql operation a table value used for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnOp {
+    Insert,
+    Update,
+}
+
+/// rename the struct fields of `value`(a map or an array of map) into the table columns.
+/// `op` will remove the columns `insert = false` or `update = false`.
+/// the fields not in `mapping` are renamed by `naming`(the [`RBatis::naming_strategy`](crate::RBatis::naming_strategy))
+pub fn to_columns(
+    value: Value,
+    mapping: &[TableColumn],
+    naming: NamingStrategy,
+    op: Option<ColumnOp>,
+) -> Value {
+    if mapping.is_empty() && naming == NamingStrategy::None {
+        return value;
+    }
+    match value {
+        Value::Array(arr) => Value::Array(
+            arr.into_iter()
+                .map(|v| to_columns(v, mapping, naming, op))
+                .collect(),
+        ),
+        Value::Map(m) => {
+            let mut columns = rbs::value::map::ValueMap::with_capacity(m.len());
+            for (k, v) in m {
+                match mapping.iter().find(|c| k.as_str() == Some(c.field)) {
+                    Some(c) => {
+                        match op {
+                            Some(ColumnOp::Insert) if !c.insert => continue,
+                            Some(ColumnOp::Update) if !c.update => continue,
+                            _ => {}
+                        }
+                        columns.insert(Value::String(c.column.to_string()), v);
+                    }
+                    None => match k {
+                        Value::String(field) => {
+                            columns.insert(Value::String(naming.to_column(&field)), v);
+                        }
+                        k => {
+                            columns.insert(k, v);
+                        }
+                    },
+                }
+            }
+            Value::Map(columns)
+        }
+        v => v,
+    }
+}
+
+/// rename the table columns of `value` back into the fields of `T`, see [`to_columns`]
+pub fn to_fields<T: DeserializeOwned>(
+    value: Value,
+    mapping: &[TableColumn],
+    naming: NamingStrategy,
+) -> Value {
+    if mapping.is_empty() && naming == NamingStrategy::None {
+        return value;
+    }
+    rename_fields(value, mapping, naming, crate::decode::field_names::<T>())
+}
+
+fn rename_fields(
+    value: Value,
+    mapping: &[TableColumn],
+    naming: NamingStrategy,
+    struct_fields: &[&str],
+) -> Value {
+    match value {
+        Value::Array(arr) => Value::Array(
+            arr.into_iter()
+                .map(|v| rename_fields(v, mapping, naming, struct_fields))
+                .collect(),
+        ),
+        Value::Map(m) => {
+            let mut fields = rbs::value::map::ValueMap::with_capacity(m.len());
+            for (k, v) in m {
+                match mapping.iter().find(|c| k.as_str() == Some(c.column)) {
+                    Some(c) => {
+                        fields.insert(Value::String(c.field.to_string()), v);
+                    }
+                    None => match k {
+                        Value::String(column) => {
+                            fields.insert(Value::String(naming.to_field(&column, struct_fields)), v);
+                        }
+                        k => {
+                            fields.insert(k, v);
+                        }
+                    },
+                }
+            }
+            Value::Map(fields)
+        }
+        v => v,
+    }
+}
+
+/// the lifecycle hooks of a table, called by the methods of `impl_insert!`, `impl_update!` and `impl_delete!`.
+/// a table without the impl is not changed, an error of the `before_*` hooks cancels the sql.
+///
+/// the generated methods take `&T`, so the `before_*` hooks are called on a clone of the table
+/// (`insert_returning`/`insert_batch_returning` call them on the tables in place)
+/// ```rust
+/// use rbatis::crud_traits::TableHooks;
+/// #[derive(Clone, serde::Serialize, serde::Deserialize)]
+/// pub struct User {
+///     pub id: Option<i64>,
+///     pub name: Option<String>,
+/// }
+/// rbatis::crud!(User{});
+/// impl TableHooks for User {
+///     fn before_insert(&mut self) -> Result<(), rbatis::Error> {
+///         match &self.name {
+///             Some(name) if !name.trim().is_empty() => {
+///                 self.name = Some(name.trim().to_string());
+///                 Ok(())
+///             }
+///             _ => Err(rbatis::Error::from("the name of user can't be empty")),
+///         }
+///     }
+/// }
+/// ```
+pub trait TableHooks: Clone {
+    fn before_insert(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// `result` is the result of the batch the table in
+    fn after_insert(&self, _result: &ExecResult) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn before_update(&mut self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn after_update(&self, _result: &ExecResult) -> Result<(), Error> {
+        Ok(())
+    }
+
+    /// `condition` is the condition map of `delete_by_map`, or the params map of the other delete methods,
+    /// for example `{"column": "id", "column_value": 1}` of `delete_by_column`
+    fn before_delete(_condition: &Value) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+
+    fn after_delete(_condition: &Value, _result: &ExecResult) -> Result<(), Error>
+    where
+        Self: Sized,
+    {
+        Ok(())
+    }
+}
+
+/// the hooks caller of the crud macros, `(&Hooks::<T>::new()).before_insert(..)`
+/// calls [`TableHooks`] if `T` impl it, otherwise [`SkipHooks`] does nothing
+#[doc(hidden)]
+pub struct Hooks<T>(PhantomData<T>);
+
+impl<T> Hooks<T> {
+    pub fn new() -> Self {
+        Hooks(PhantomData)
+    }
+}
+
+impl<T> Default for Hooks<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[doc(hidden)]
+pub trait CallHooks<T> {
+    /// the hooked clone of `tables`
+    fn before_insert(&self, tables: &[T]) -> Result<Option<Vec<T>>, Error>;
+    fn before_insert_mut(&self, tables: &mut [T]) -> Result<(), Error>;
+    fn after_insert(&self, tables: &[T], result: &ExecResult) -> Result<(), Error>;
+    /// the hooked clone of `table`
+    fn before_update(&self, table: &T) -> Result<Option<T>, Error>;
+    fn after_update(&self, table: &T, result: &ExecResult) -> Result<(), Error>;
+    fn before_delete(&self, condition: impl FnOnce() -> Value) -> Result<Option<Value>, Error>;
+    fn after_delete(&self, condition: Option<Value>, result: &ExecResult) -> Result<(), Error>;
+}
+
+impl<T: TableHooks> CallHooks<T> for Hooks<T> {
+    fn before_insert(&self, tables: &[T]) -> Result<Option<Vec<T>>, Error> {
+        let mut tables = tables.to_vec();
+        self.before_insert_mut(&mut tables)?;
+        Ok(Some(tables))
+    }
+
+    fn before_insert_mut(&self, tables: &mut [T]) -> Result<(), Error> {
+        for table in tables {
+            table.before_insert()?;
+        }
+        Ok(())
+    }
+
+    fn after_insert(&self, tables: &[T], result: &ExecResult) -> Result<(), Error> {
+        for table in tables {
+            table.after_insert(result)?;
+        }
+        Ok(())
+    }
+
+    fn before_update(&self, table: &T) -> Result<Option<T>, Error> {
+        let mut table = table.clone();
+        table.before_update()?;
+        Ok(Some(table))
+    }
+
+    fn after_update(&self, table: &T, result: &ExecResult) -> Result<(), Error> {
+        table.after_update(result)
+    }
+
+    fn before_delete(&self, condition: impl FnOnce() -> Value) -> Result<Option<Value>, Error> {
+        let condition = condition();
+        T::before_delete(&condition)?;
+        Ok(Some(condition))
+    }
+
+    fn after_delete(&self, condition: Option<Value>, result: &ExecResult) -> Result<(), Error> {
+        T::after_delete(&condition.unwrap_or_default(), result)
+    }
+}
+
+#[doc(hidden)]
+pub trait SkipHooks<T> {
+    fn before_insert(&self, _tables: &[T]) -> Result<Option<Vec<T>>, Error> {
+        Ok(None)
+    }
+    fn before_insert_mut(&self, _tables: &mut [T]) -> Result<(), Error> {
+        Ok(())
+    }
+    fn after_insert(&self, _tables: &[T], _result: &ExecResult) -> Result<(), Error> {
+        Ok(())
+    }
+    fn before_update(&self, _table: &T) -> Result<Option<T>, Error> {
+        Ok(None)
+    }
+    fn after_update(&self, _table: &T, _result: &ExecResult) -> Result<(), Error> {
+        Ok(())
+    }
+    fn before_delete(&self, _condition: impl FnOnce() -> Value) -> Result<Option<Value>, Error> {
+        Ok(None)
+    }
+    fn after_delete(&self, _condition: Option<Value>, _result: &ExecResult) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+impl<T> SkipHooks<T> for &Hooks<T> {}
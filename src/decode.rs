@@ -1,30 +1,173 @@
 //! Types and traits for decoding values from the database.
+use rbs::value::map::ValueMap;
 use rbs::Value;
-use serde::de::DeserializeOwned;
+use serde::de::{DeserializeOwned, DeserializeSeed, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::cell::Cell;
+use std::collections::HashMap;
 
 use crate::Error;
 
 /// decode json vec to an object
 /// support decode types:
 /// Value,BigDecimal, i8..i64,u8..u64,i64,bool,String
-/// or object used rbs::Value macro object
+/// or object used rbs::Value macro object.
+/// the rows are also decoded by the column position into tuples(`Vec<(i64, String)>`),
+/// a single column into the scalars and the newtypes(`Vec<i64>`).
+/// a map is a single row by the column names, see [`decode_pairs`] for the rows of two columns
 pub fn decode_ref<T: ?Sized>(values: &Value) -> Result<T, Error>
 where
     T: DeserializeOwned,
 {
-    // try to identify type
-    let is_array = rbs::from_value::<T>(Value::Array(vec![])).is_ok();
-    if is_array {
+    decode_values(values)
+}
+
+/// decode with the column names renamed into the field names of `T` by `naming`,
+/// the executors and the crud macros use the [`naming_strategy`](crate::RBatis::naming_strategy) of `RBatis`
+pub fn decode_naming<T: ?Sized>(values: Value, naming: NamingStrategy) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    if naming == NamingStrategy::None {
+        return decode_values(&values);
+    }
+    decode_values(&naming.rename_rows(values, field_names::<T>()))
+}
+
+fn decode_values<T: ?Sized>(values: &Value) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    match (shape_of::<T>(), values) {
         //decode array
-        Ok(rbs::from_value_ref(values)?)
-    } else {
-        match values {
-            Value::Array(datas) => Ok(try_decode_map(datas)?),
-            _ => Err(Error::from("decode an not array value")),
+        (Shape::Seq(row), Value::Array(rows)) => match *row {
+            Shape::Scalar | Shape::Tuple | Shape::Seq(_) => {
+                let positional = rows.iter().map(|v| to_positional(v, &row)).collect();
+                decode_rows(rows, &Value::Array(positional))
+            }
+            _ => decode_rows(rows, values),
+        },
+        (Shape::Seq(_), _) => rbs::from_value_ref(values)
+            .map_err(|e| decode_error::<T>(&[], &Position::default(), e)),
+        //`Value` and the other self-describing types, try to identify type
+        (Shape::Any, _) if rbs::from_value::<T>(Value::Array(vec![])).is_ok() => {
+            rbs::from_value_ref(values).map_err(|e| decode_error::<T>(&[], &Position::default(), e))
+        }
+        (_, Value::Array(datas)) => try_decode_map(datas),
+        _ => Err(Error::from("decode an not array value")),
+    }
+}
+
+/// how the column names of the database map to the field names of the struct.
+/// it's used by `decode` (column => field) and the crud macros (field => column),
+/// set it by [`RBatis::naming_strategy`](crate::RBatis::naming_strategy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NamingStrategy {
+    /// use the names as is
+    #[default]
+    None,
+    /// column `create_time`(or `CREATE_TIME`) <=> field `createTime`
+    SnakeToCamel,
+    /// column `createTime` <=> field `create_time`
+    CamelToSnake,
+    /// column `CREATETIME` => field `createTime`, the column is matched with the struct fields ignoring
+    /// the ascii case, the fields are written as is
+    CaseInsensitive,
+}
+
+impl NamingStrategy {
+    /// the field name of a column name, `fields` are the fields of the struct(only used by `CaseInsensitive`)
+    pub fn to_field(&self, column: &str, fields: &[&str]) -> String {
+        match self {
+            NamingStrategy::None => column.to_string(),
+            NamingStrategy::SnakeToCamel => snake_to_camel(column),
+            NamingStrategy::CamelToSnake => camel_to_snake(column),
+            NamingStrategy::CaseInsensitive => fields
+                .iter()
+                .find(|f| f.eq_ignore_ascii_case(column))
+                .unwrap_or(&column)
+                .to_string(),
+        }
+    }
+
+    /// the column name of a field name
+    pub fn to_column(&self, field: &str) -> String {
+        match self {
+            NamingStrategy::None | NamingStrategy::CaseInsensitive => field.to_string(),
+            NamingStrategy::SnakeToCamel => camel_to_snake(field),
+            NamingStrategy::CamelToSnake => snake_to_camel(field),
+        }
+    }
+
+    /// rename the columns of every row(map) into the field names
+    pub fn rename_rows(&self, rows: Value, fields: &[&str]) -> Value {
+        match rows {
+            Value::Array(rows) => Value::Array(
+                rows.into_iter()
+                    .map(|v| self.rename_rows(v, fields))
+                    .collect(),
+            ),
+            Value::Map(row) => {
+                let mut m = ValueMap::with_capacity(row.len());
+                for (k, v) in row {
+                    match k {
+                        Value::String(column) => {
+                            m.insert(Value::String(self.to_field(&column, fields)), v);
+                        }
+                        k => {
+                            m.insert(k, v);
+                        }
+                    }
+                }
+                Value::Map(m)
+            }
+            v => v,
         }
     }
 }
 
+/// `create_time` or `CREATE_TIME` => `createTime`, a name without `_` keep as is unless it's all upper case
+fn snake_to_camel(name: &str) -> String {
+    if !name.contains('_') {
+        if name.chars().any(|c| c.is_ascii_lowercase()) {
+            return name.to_string();
+        }
+        return name.to_lowercase();
+    }
+    let mut camel = String::with_capacity(name.len());
+    for word in name.split('_').filter(|v| !v.is_empty()) {
+        let word = word.to_lowercase();
+        if camel.is_empty() {
+            camel.push_str(&word);
+        } else {
+            let mut chars = word.chars();
+            if let Some(c) = chars.next() {
+                camel.push(c.to_ascii_uppercase());
+                camel.push_str(chars.as_str());
+            }
+        }
+    }
+    camel
+}
+
+/// `createTime` => `create_time`, `userID` => `user_id`
+pub(crate) fn camel_to_snake(name: &str) -> String {
+    let mut snake = String::with_capacity(name.len() + 4);
+    let mut prev_lower = false;
+    for c in name.chars() {
+        if c.is_ascii_uppercase() {
+            if prev_lower {
+                snake.push('_');
+            }
+            snake.push(c.to_ascii_lowercase());
+            prev_lower = false;
+        } else {
+            snake.push(c);
+            prev_lower = c.is_ascii_lowercase() || c.is_ascii_digit();
+        }
+    }
+    snake
+}
+
 pub fn decode<T: ?Sized>(bs: Value) -> Result<T, Error>
 where
     T: DeserializeOwned,
@@ -32,11 +175,15 @@ where
     decode_ref(&bs)
 }
 
-//decode doc or one type
+//decode doc or one type.
+//* a scalar(the primitives, the newtypes) is decoded from the single column
+//* a tuple is decoded by the column position
+//* a map or a struct is decoded by the column names of a single row
 pub fn try_decode_map<T>(datas: &Vec<Value>) -> Result<T, Error>
 where
     T: DeserializeOwned,
 {
+    let shape = shape_of::<T>();
     //decode struct
     if datas.len() > 1 {
         return Err(Error::from(format!(
@@ -46,42 +193,744 @@ where
     }
     //single try decode
     if datas.is_empty() {
-        return Ok(rbs::from_value::<T>(Value::Null)?);
+        let v = rbs::from_value::<T>(Value::Null);
+        if v.is_err() && shape == Shape::Map {
+            return Ok(rbs::from_value::<T>(Value::Map(ValueMap::new()))?);
+        }
+        return Ok(v?);
     }
     let m = datas.get(0).unwrap_or(&Value::Null);
-    match &m {
-        Value::Map(map) => {
+    let positional;
+    let row = match shape {
+        Shape::Scalar | Shape::Tuple | Shape::Seq(_) => {
+            positional = to_positional(m, &shape);
+            &positional
+        }
+        _ => m,
+    };
+    let position = Position::default();
+    position.row.set(Some(0));
+    T::deserialize(Row::new(row, &position)).map_err(|e| decode_error::<T>(datas, &position, e))
+}
+
+/// decode the rows of two columns into a map, the first column is the key and the second is the value.
+/// ```rust
+/// use std::collections::HashMap;
+///
+/// let rows = rbs::Value::Array(vec![
+///     rbs::value! {"id": 1, "name": "a"},
+///     rbs::value! {"id": 2, "name": "b"},
+/// ]);
+/// let names: HashMap<i64, String> = rbatis::decode_pairs(rows).unwrap();
+/// assert_eq!(names[&2], "b");
+/// ```
+pub fn decode_pairs<T>(rows: Value) -> Result<T, Error>
+where
+    T: DeserializeOwned,
+{
+    let rows = match rows {
+        Value::Array(rows) => rows,
+        Value::Null => vec![],
+        _ => return Err(Error::from("decode an not array value")),
+    };
+    let mut map = ValueMap::with_capacity(rows.len());
+    for (idx, row) in rows.iter().enumerate() {
+        match to_positional(row, &Shape::Tuple) {
+            Value::Array(mut kv) if kv.len() == 2 => {
+                let v = kv.pop().unwrap_or_default();
+                let k = kv.pop().unwrap_or_default();
+                map.insert(k, v);
+            }
+            _ => {
+                return Err(Error::from(format!(
+                    "decode row {} into `{}` fail: the pairs need 2 columns",
+                    idx,
+                    std::any::type_name::<T>()
+                )))
+            }
+        }
+    }
+    rbs::from_value::<T>(Value::Map(map)).map_err(|e| decode_error::<T>(&rows, &Position::default(), e))
+}
+
+/// the row by the column position. the row of a single column is the value itself if it's decoded into a scalar
+fn to_positional(row: &Value, shape: &Shape) -> Value {
+    match row {
+        Value::Map(map) if *shape == Shape::Scalar => {
             if map.len() == 1 {
-                //try one
-                let type_name = std::any::type_name::<T>();
-                if type_name == std::any::type_name::<i32>()
-                    || type_name == std::any::type_name::<i64>()
-                    || type_name == std::any::type_name::<f32>()
-                    || type_name == std::any::type_name::<f64>()
-                    || type_name == std::any::type_name::<u32>()
-                    || type_name == std::any::type_name::<u64>()
-                    || type_name == std::any::type_name::<String>()
-                    || type_name == std::any::type_name::<bool>()
-                    || type_name == std::any::type_name::<Option<i32>>()
-                    || type_name == std::any::type_name::<Option<i64>>()
-                    || type_name == std::any::type_name::<Option<f32>>()
-                    || type_name == std::any::type_name::<Option<f64>>()
-                    || type_name == std::any::type_name::<Option<u32>>()
-                    || type_name == std::any::type_name::<Option<u64>>()
-                    || type_name == std::any::type_name::<Option<String>>()
-                    || type_name == std::any::type_name::<Option<bool>>()
-                    || type_name.starts_with("rbdc::types::")
-                    || type_name.starts_with("core::option::Option<rbdc::types::")
-                {
-                    if let Some((_, value)) = map.into_iter().next(){
-                        return Ok(rbs::from_value_ref::<T>(value)?);
+                map.into_iter().next().map(|(_, v)| v.clone()).unwrap_or_default()
+            } else {
+                row.clone()
+            }
+        }
+        Value::Map(map) => Value::Array(map.into_iter().map(|(_, v)| v.clone()).collect()),
+        _ => row.clone(),
+    }
+}
+
+/// the shape of a type, it's probed by the `deserialize_*` method the type calls
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    /// `Value` and the other types of `deserialize_any`, decided by the data
+    Any,
+    /// a single column, the primitives, the enums and the newtypes of them
+    Scalar,
+    /// the columns by position
+    Tuple,
+    /// the columns by name, the fields of the struct
+    Struct(&'static [&'static str]),
+    /// the columns by name, or the rows of two columns
+    Map,
+    /// the rows
+    Seq(Box<Shape>),
+}
+
+fn shape_of<T: DeserializeOwned>() -> Shape {
+    match T::deserialize(ShapeProbe) {
+        Err(Probed(shape)) => shape,
+        Ok(_) => Shape::Any,
+    }
+}
+
+/// the fields of a struct `T`, or the struct of the rows(`Vec<T>`,`Option<T>`). empty if not a struct
+pub(crate) fn field_names<T: DeserializeOwned>() -> &'static [&'static str] {
+    let mut shape = shape_of::<T>();
+    while let Shape::Seq(row) = shape {
+        shape = *row;
+    }
+    match shape {
+        Shape::Struct(fields) => fields,
+        _ => &[],
+    }
+}
+
+/// the error of [`ShapeProbe`] is the probed shape
+#[derive(Debug)]
+struct Probed(Shape);
+
+impl std::fmt::Display for Probed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}", self.0)
+    }
+}
+
+impl std::error::Error for Probed {}
+
+impl serde::de::Error for Probed {
+    fn custom<M: std::fmt::Display>(_msg: M) -> Self {
+        Probed(Shape::Any)
+    }
+}
+
+/// a deserializer without data, it only records the shape the type asked for
+struct ShapeProbe;
+
+macro_rules! probe_scalar {
+    ($($method:ident)*) => {
+        $(fn $method<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Probed> {
+            Err(Probed(Shape::Scalar))
+        })*
+    };
+}
+
+impl<'de> Deserializer<'de> for ShapeProbe {
+    type Error = Probed;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Probed> {
+        Err(Probed(Shape::Any))
+    }
+
+    probe_scalar! {
+        deserialize_bool deserialize_i8 deserialize_i16 deserialize_i32 deserialize_i64 deserialize_i128
+        deserialize_u8 deserialize_u16 deserialize_u32 deserialize_u64 deserialize_u128
+        deserialize_f32 deserialize_f64 deserialize_char deserialize_str deserialize_string
+        deserialize_bytes deserialize_byte_buf deserialize_unit deserialize_identifier
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Probed> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, Probed> {
+        Err(Probed(Shape::Scalar))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Probed> {
+        //a newtype of `Value`(for example `DateTime`) is a single column too
+        match visitor.visit_newtype_struct(self) {
+            Err(Probed(Shape::Any)) | Ok(_) => Err(Probed(Shape::Scalar)),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Probed> {
+        match visitor.visit_seq(self) {
+            Err(Probed(row)) => Err(Probed(Shape::Seq(Box::new(row)))),
+            Ok(_) => Err(Probed(Shape::Seq(Box::new(Shape::Any)))),
+        }
+    }
+
+    fn deserialize_tuple<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value, Probed> {
+        Err(Probed(Shape::Tuple))
+    }
+
+    fn deserialize_tuple_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Probed> {
+        Err(Probed(Shape::Tuple))
+    }
+
+    fn deserialize_map<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Probed> {
+        Err(Probed(Shape::Map))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Probed> {
+        Err(Probed(Shape::Struct(fields)))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Probed> {
+        Err(Probed(Shape::Scalar))
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Probed> {
+        Err(Probed(Shape::Any))
+    }
+}
+
+/// probe the element of a sequence
+impl<'de> SeqAccess<'de> for ShapeProbe {
+    type Error = Probed;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, Probed> {
+        seed.deserialize(ShapeProbe).map(Some)
+    }
+}
+
+/// decode the rows(`value` is `rows` or the positional rows of them) once,
+/// the row index and the column are recorded for the error
+fn decode_rows<T: DeserializeOwned>(rows: &[Value], value: &Value) -> Result<T, Error> {
+    let position = Position::default();
+    T::deserialize(Rows {
+        value,
+        position: &position,
+    })
+    .map_err(|e| decode_error::<T>(rows, &position, e))
+}
+
+/// the row index and the column which is decoding
+#[derive(Default)]
+struct Position<'a> {
+    row: Cell<Option<usize>>,
+    column: Cell<Option<&'a Value>>,
+    /// the row is decoded by the column names, not the single column value
+    by_name: Cell<bool>,
+}
+
+/// the deserializer of the rows(a `Value::Array`), it records the index of the row
+struct Rows<'a> {
+    value: &'a Value,
+    position: &'a Position<'a>,
+}
+
+impl<'de, 'a> Deserializer<'de> for Rows<'a> {
+    type Error = rbs::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, rbs::Error> {
+        match self.value {
+            Value::Array(rows) => {
+                let mut access = RowsAccess {
+                    iter: rows.iter().enumerate(),
+                    position: self.position,
+                };
+                let v = visitor.visit_seq(&mut access)?;
+                if access.iter.len() == 0 {
+                    Ok(v)
+                } else {
+                    Err(serde::de::Error::invalid_length(
+                        rows.len(),
+                        &"fewer elements in array",
+                    ))
+                }
+            }
+            v => v.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, rbs::Error> {
+        match self.value {
+            Value::Array(_) => visitor.visit_some(self),
+            v => v.deserialize_option(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, rbs::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, rbs::Error> {
+        self.value.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, rbs::Error> {
+        self.value.deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string unit seq
+        bytes byte_buf map tuple_struct struct identifier tuple ignored_any
+    }
+}
+
+struct RowsAccess<'a, I> {
+    iter: I,
+    position: &'a Position<'a>,
+}
+
+impl<'de, 'a, I> SeqAccess<'de> for RowsAccess<'a, I>
+where
+    I: ExactSizeIterator<Item = (usize, &'a Value)>,
+{
+    type Error = rbs::Error;
+
+    fn next_element_seed<S: DeserializeSeed<'de>>(
+        &mut self,
+        seed: S,
+    ) -> Result<Option<S::Value>, rbs::Error> {
+        match self.iter.next() {
+            Some((idx, row)) => {
+                self.position.row.set(Some(idx));
+                seed.deserialize(Row::new(row, self.position)).map(Some)
+            }
+            None => {
+                self.position.row.set(None);
+                Ok(None)
+            }
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// the deserializer of a row, it records the column of a map row
+struct Row<'a> {
+    value: &'a Value,
+    position: &'a Position<'a>,
+}
+
+impl<'a> Row<'a> {
+    fn new(value: &'a Value, position: &'a Position<'a>) -> Self {
+        position.column.set(None);
+        position.by_name.set(false);
+        Self { value, position }
+    }
+}
+
+impl<'de, 'a> Deserializer<'de> for Row<'a> {
+    type Error = rbs::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, rbs::Error> {
+        match self.value {
+            Value::Map(row) => {
+                self.position.by_name.set(true);
+                let mut access = RowAccess {
+                    iter: row.into_iter(),
+                    value: None,
+                    position: self.position,
+                };
+                let v = visitor.visit_map(&mut access)?;
+                if access.iter.len() == 0 {
+                    Ok(v)
+                } else {
+                    Err(serde::de::Error::invalid_length(
+                        row.len(),
+                        &"fewer elements in map",
+                    ))
+                }
+            }
+            v => v.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, rbs::Error> {
+        match self.value {
+            Value::Map(_) => visitor.visit_some(self),
+            v => v.deserialize_option(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, rbs::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_unit_struct<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, rbs::Error> {
+        self.value.deserialize_unit_struct(name, visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, rbs::Error> {
+        self.value.deserialize_enum(name, variants, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string unit seq
+        bytes byte_buf map tuple_struct struct identifier tuple ignored_any
+    }
+}
+
+struct RowAccess<'a, I> {
+    iter: I,
+    value: Option<&'a Value>,
+    position: &'a Position<'a>,
+}
+
+impl<'de, 'a, I> MapAccess<'de> for RowAccess<'a, I>
+where
+    I: ExactSizeIterator<Item = (&'a Value, &'a Value)>,
+{
+    type Error = rbs::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, rbs::Error> {
+        match self.iter.next() {
+            Some((column, value)) => {
+                self.position.column.set(Some(column));
+                self.value = Some(value);
+                seed.deserialize(column).map(Some)
+            }
+            None => {
+                //the error after all the columns(a missing field) is not of a column
+                self.position.column.set(None);
+                Ok(None)
+            }
+        }
+    }
+
+    fn next_value_seed<S: DeserializeSeed<'de>>(&mut self, seed: S) -> Result<S::Value, rbs::Error> {
+        match self.value.take() {
+            Some(value) => seed.deserialize(value),
+            None => Err(serde::de::Error::custom("value is missing")),
+        }
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.iter.len())
+    }
+}
+
+/// add the row index, column name, the `Value` type and the target type into the error of serde,
+/// for example:
+/// `decode row 1, column `age`(String) into `i32` fail: invalid type: string "a", expected i32`
+fn decode_error<T: ?Sized>(rows: &[Value], position: &Position, e: rbs::Error) -> Error {
+    let mut msg = "decode".to_string();
+    if let Some(idx) = position.row.get() {
+        msg.push_str(&format!(" row {}", idx));
+        if let Some(Value::Map(row)) = rows.get(idx) {
+            //a single column is decoded as the value itself
+            let column = match position.column.get() {
+                Some(column) => Some(column),
+                None if row.len() == 1 && !position.by_name.get() => {
+                    row.into_iter().next().map(|(k, _)| k)
+                }
+                None => None,
+            };
+            if let Some(column) = column {
+                msg.push_str(&format!(
+                    ", column `{}`({})",
+                    column.as_str().unwrap_or_default(),
+                    value_type(row.get(column))
+                ));
+            }
+        }
+    }
+    Error::from(format!(
+        "{} into `{}` fail: {}",
+        msg,
+        std::any::type_name::<T>(),
+        e
+    ))
+}
+
+/// the variant name of the value
+pub(crate) fn value_type(value: &Value) -> String {
+    match value {
+        Value::Null => "Null".to_string(),
+        Value::Bool(_) => "Bool".to_string(),
+        Value::I32(_) => "I32".to_string(),
+        Value::I64(_) => "I64".to_string(),
+        Value::U32(_) => "U32".to_string(),
+        Value::U64(_) => "U64".to_string(),
+        Value::F32(_) => "F32".to_string(),
+        Value::F64(_) => "F64".to_string(),
+        Value::String(_) => "String".to_string(),
+        Value::Binary(_) => "Binary".to_string(),
+        Value::Array(_) => "Array".to_string(),
+        Value::Map(_) => "Map".to_string(),
+        Value::Ext(name, _) => format!("Ext({})", name),
+    }
+}
+
+/// decode the rows of a join query into nested structs by the column prefix,
+/// like the `resultMap` of MyBatis with `association`/`collection`.
+///
+/// * the nested fields are declared by [`ResultMap::association`] and [`ResultMap::collection`],
+///   a column `author__name` is the `name` of the field `author`, the other columns are kept as is
+/// * the repeated parent rows are grouped and the children of a collection are pushed into a `Vec`
+/// * the parent rows are grouped by the [`ResultMap::id`] columns, or all the own columns if not set
+/// * a child row of all null columns(a left join without match) is skipped
+///
+/// for example:
+/// ```rust
+/// use rbatis::ResultMap;
+/// #[derive(serde::Deserialize)]
+/// pub struct Author { pub id: i32, pub name: String }
+/// #[derive(serde::Deserialize)]
+/// pub struct Comment { pub id: i32, pub body: String }
+/// #[derive(serde::Deserialize)]
+/// pub struct Post { pub id: i32, pub author: Author, pub comments: Vec<Comment> }
+///
+/// //select p.id, a.id as author__id, a.name as author__name, c.id as comments__id, c.body as comments__body
+/// //from post p join author a on p.author_id = a.id left join comment c on c.post_id = p.id
+/// let rows = rbs::Value::Array(vec![
+///     rbs::to_value!{"id": 1, "author__id": 1, "author__name": "a", "comments__id": 1, "comments__body": "x"},
+///     rbs::to_value!{"id": 1, "author__id": 1, "author__name": "a", "comments__id": 2, "comments__body": "y"},
+///     rbs::to_value!{"id": 2, "author__id": 1, "author__name": "a", "comments__id": (), "comments__body": ()},
+/// ]);
+/// let posts: Vec<Post> = ResultMap::new()
+///     .id("id")
+///     .association("author", ResultMap::new())
+///     .collection("comments", ResultMap::new())
+///     .decode(rows)
+///     .unwrap();
+/// assert_eq!(posts.len(), 2);
+/// assert_eq!(posts[0].author.name, "a");
+/// assert_eq!(posts[0].comments.len(), 2);
+/// assert_eq!(posts[1].comments.len(), 0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResultMap {
+    prefix: Option<String>,
+    separator: String,
+    ids: Vec<String>,
+    children: Vec<NestedField>,
+}
+
+#[derive(Debug, Clone)]
+struct NestedField {
+    field: String,
+    map: ResultMap,
+    collection: bool,
+}
+
+impl Default for ResultMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ResultMap {
+    pub fn new() -> Self {
+        Self {
+            prefix: None,
+            separator: "__".to_string(),
+            ids: vec![],
+            children: vec![],
+        }
+    }
+
+    /// the column prefix of this nested map, default is `{field}__`
+    pub fn prefix(mut self, prefix: &str) -> Self {
+        self.prefix = Some(prefix.to_string());
+        self
+    }
+
+    /// the separator of the column prefix, default is `__`
+    pub fn separator(mut self, separator: &str) -> Self {
+        self.separator = separator.to_string();
+        self
+    }
+
+    /// the column to group the repeated rows, can be called many times for a composite key
+    pub fn id(mut self, column: &str) -> Self {
+        self.ids.push(column.to_string());
+        self
+    }
+
+    /// a nested struct field
+    pub fn association(mut self, field: &str, map: ResultMap) -> Self {
+        self.children.push(NestedField {
+            field: field.to_string(),
+            map,
+            collection: false,
+        });
+        self
+    }
+
+    /// a nested `Vec` field
+    pub fn collection(mut self, field: &str, map: ResultMap) -> Self {
+        self.children.push(NestedField {
+            field: field.to_string(),
+            map,
+            collection: true,
+        });
+        self
+    }
+
+    /// decode the rows(`Value::Array` of the query) into `Vec<T>`
+    pub fn decode<T: DeserializeOwned>(&self, rows: Value) -> Result<Vec<T>, Error> {
+        let rows = match rows {
+            Value::Array(rows) => rows,
+            Value::Null => vec![],
+            _ => return Err(Error::from("decode an not array value")),
+        };
+        rbs::from_value(Value::Array(self.map_rows(rows, "", false)?))
+    }
+
+    /// `prefix` is the column prefix of this map in the query, only for the error message
+    fn map_rows(&self, rows: Vec<Value>, prefix: &str, skip_null: bool) -> Result<Vec<Value>, Error> {
+        let prefixes: Vec<String> = self
+            .children
+            .iter()
+            .map(|c| {
+                c.map
+                    .prefix
+                    .clone()
+                    .unwrap_or_else(|| format!("{}{}", c.field, self.separator))
+            })
+            .collect();
+        //(own columns, the rows of every child)
+        let mut groups: Vec<(ValueMap, Vec<Vec<Value>>)> = vec![];
+        let mut group_index: HashMap<Vec<Value>, usize> = HashMap::new();
+        for row in rows {
+            let row = match row {
+                Value::Map(m) => m,
+                _ => continue,
+            };
+            if skip_null && (&row).into_iter().all(|(_, v)| v.is_null()) {
+                continue;
+            }
+            let mut own = ValueMap::new();
+            let mut child_rows: Vec<ValueMap> = vec![ValueMap::new(); self.children.len()];
+            for (k, v) in row {
+                let prefix = k
+                    .as_str()
+                    .and_then(|key| prefixes.iter().position(|p| key.starts_with(p.as_str())));
+                match prefix {
+                    Some(idx) => {
+                        let column = k.as_str().unwrap_or_default()[prefixes[idx].len()..].to_string();
+                        child_rows[idx].insert(Value::String(column), v);
+                    }
+                    None => {
+                        own.insert(k, v);
                     }
                 }
             }
+            let key: Vec<Value> = if self.ids.is_empty() {
+                (&own).into_iter().map(|(_, v)| group_key(v)).collect()
+            } else {
+                let mut key = Vec::with_capacity(self.ids.len());
+                for id in &self.ids {
+                    match own.0.get(&Value::String(id.clone())) {
+                        Some(v) => key.push(group_key(v)),
+                        None => {
+                            return Err(Error::from(format!(
+                                "decode ResultMap fail: the id column `{}{}` not in the row",
+                                prefix, id
+                            )));
+                        }
+                    }
+                }
+                key
+            };
+            let idx = *group_index.entry(key).or_insert_with(|| {
+                groups.push((own, vec![vec![]; self.children.len()]));
+                groups.len() - 1
+            });
+            for (i, child) in child_rows.into_iter().enumerate() {
+                groups[idx].1[i].push(Value::Map(child));
+            }
         }
-        _ => {}
+        let mut values = Vec::with_capacity(groups.len());
+        for (mut own, child_rows) in groups {
+            for ((child, rows), child_prefix) in self.children.iter().zip(child_rows).zip(&prefixes) {
+                let mut items = child
+                    .map
+                    .map_rows(rows, &format!("{}{}", prefix, child_prefix), true)?;
+                let value = if child.collection {
+                    Value::Array(items)
+                } else if items.is_empty() {
+                    Value::Null
+                } else {
+                    items.swap_remove(0)
+                };
+                own.insert(Value::String(child.field.clone()), value);
+            }
+            values.push(Value::Map(own));
+        }
+        Ok(values)
+    }
+}
+
+/// the integers of the different drivers(`I32(1)`,`I64(1)`,`U64(1)`) are the same group
+pub(crate) fn group_key(v: &Value) -> Value {
+    match v {
+        Value::I32(n) => Value::I64(*n as i64),
+        Value::U32(n) => Value::I64(*n as i64),
+        Value::U64(n) if *n <= i64::MAX as u64 => Value::I64(*n as i64),
+        v => v.clone(),
     }
-    Ok(rbs::from_value_ref::<T>(m)?)
 }
 
 pub fn is_debug_mode() -> bool {
@@ -106,6 +955,50 @@ mod test {
     use rbs::Value;
     use std::collections::HashMap;
 
+    #[test]
+    fn test_decode_result_map() {
+        use crate::decode::ResultMap;
+        #[derive(serde::Deserialize, Debug)]
+        struct Like {
+            pub user: String,
+        }
+        #[derive(serde::Deserialize, Debug)]
+        struct Comment {
+            pub id: i32,
+            pub likes: Vec<Like>,
+        }
+        #[derive(serde::Deserialize, Debug)]
+        struct Post {
+            pub id: i32,
+            pub comments: Vec<Comment>,
+        }
+        let rows = Value::Array(vec![
+            rbs::value! {"id": 1, "c_id": 1, "c_l_user": "a"},
+            rbs::value! {"id": 1, "c_id": 1, "c_l_user": "b"},
+            rbs::value! {"id": 1, "c_id": 2, "c_l_user": Value::Null},
+            rbs::value! {"id": 2, "c_id": Value::Null, "c_l_user": Value::Null},
+        ]);
+        let posts: Vec<Post> = ResultMap::new()
+            .id("id")
+            .collection(
+                "comments",
+                ResultMap::new()
+                    .prefix("c_")
+                    .id("id")
+                    .collection("likes", ResultMap::new().prefix("l_")),
+            )
+            .decode(rows)
+            .unwrap();
+        assert_eq!(posts.len(), 2);
+        assert_eq!(posts[1].id, 2);
+        assert_eq!(posts[0].comments.len(), 2);
+        assert_eq!(posts[0].comments[1].id, 2);
+        assert_eq!(posts[0].comments[0].likes.len(), 2);
+        assert_eq!(posts[0].comments[0].likes[1].user, "b");
+        assert_eq!(posts[0].comments[1].likes.len(), 0);
+        assert_eq!(posts[1].comments.len(), 0);
+    }
+
     #[test]
     fn test_decode_hashmap() {
         let mut v = ValueMap::new();
@@ -1,4 +1,4 @@
-use crate::decode::decode;
+use crate::decode::decode_naming;
 use crate::intercept::ResultType;
 use crate::rbatis::RBatis;
 use crate::Error;
@@ -21,6 +21,8 @@ pub trait Executor: RBatisRef + Send + Sync {
         std::any::type_name::<Self>()
     }
     fn exec(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>>;
+    /// the rows of the first result set. a statement of the multiple result sets(a stored procedure of
+    /// mssql/mysql) is not supported, `rbdc::db::Connection` has no api to read the next result set
     fn query(&self, sql: &str, args: Vec<Value>) -> BoxFuture<'_, Result<Value, Error>>;
     fn as_any(&self) -> &dyn Any
     where
@@ -85,7 +87,7 @@ impl RBatisConnExecutor {
         T: DeserializeOwned,
     {
         let v = Executor::query(self, sql, args).await?;
-        Ok(decode(v)?)
+        Ok(decode_naming(v, self.rb_ref().naming_strategy)?)
     }
 }
 
@@ -97,7 +99,7 @@ impl Executor for RBatisConnExecutor {
     fn exec(&self, sql: &str, mut args: Vec<Value>) -> BoxFuture<'_, Result<ExecResult, Error>> {
         let mut sql = sql.to_string();
         Box::pin(async move {
-            let rb_task_id = self.rb.task_id_generator.generate();
+            let rb_task_id = self.rb.task_id_generator.generate_i64();
             let mut before_result = Err(Error::from(""));
             for item in self.rb_ref().intercepts.iter() {
                 let next = item
@@ -144,7 +146,7 @@ impl Executor for RBatisConnExecutor {
     fn query(&self, sql: &str, mut args: Vec<Value>) -> BoxFuture<'_, Result<Value, Error>> {
         let mut sql = sql.to_string();
         Box::pin(async move {
-            let rb_task_id = self.rb.task_id_generator.generate();
+            let rb_task_id = self.rb.task_id_generator.generate_i64();
             let mut before_result = Err(Error::from(""));
             for item in self.rb_ref().intercepts.iter() {
                 let next = item
@@ -166,7 +168,7 @@ impl Executor for RBatisConnExecutor {
             }
             let mut conn = self.conn.lock().await;
             let mut args_after = args.clone();
-            let mut result = conn.get_values(&sql, args).await;
+            let mut result = conn.exec_decode(&sql, args).await.map(|v| match v { Value::Array(arr) => arr, _ => vec![] });
             for item in self.rb_ref().intercepts.iter() {
                 let next = item
                     .after(
@@ -202,7 +204,7 @@ impl RBatisConnExecutor {
             let mut conn = self.conn.into_inner();
             conn.begin().await?;
             Ok(RBatisTxExecutor::new(
-                self.rb.task_id_generator.generate(),
+                self.rb.task_id_generator.generate_i64(),
                 self.rb,
                 conn,
             ))
@@ -272,7 +274,7 @@ impl<'a> RBatisTxExecutor {
         T: DeserializeOwned,
     {
         let v = Executor::query(self, sql, args).await?;
-        Ok(decode(v)?)
+        Ok(decode_naming(v, self.rb_ref().naming_strategy)?)
     }
 
     pub fn begin(self) -> BoxFuture<'static, Result<Self, Error>> {
@@ -384,8 +386,8 @@ impl Executor for RBatisTxExecutor {
             }
             let mut conn = self.conn.lock().await;
             let mut args_after = args.clone();
-            let conn = conn.get_values(&sql, args);
-            let mut result = conn.await;
+            let conn = conn.exec_decode(&sql, args);
+            let mut result = conn.await.map(|v| match v { Value::Array(arr) => arr, _ => vec![] });
             for item in self.rb_ref().intercepts.iter() {
                 let next = item
                     .after(
@@ -557,7 +559,7 @@ impl RBatis {
     {
         let conn = self.acquire().await?;
         let v = conn.query(sql, args).await?;
-        Ok(decode(v)?)
+        Ok(decode_naming(v, self.rb_ref().naming_strategy)?)
     }
 }
 
@@ -3,7 +3,7 @@ pub extern crate rbatis_codegen;
 extern crate rbatis_macro_driver;
 pub extern crate rbdc;
 
-pub use rbatis_macro_driver::{html_sql, py_sql, snake_name, sql};
+pub use rbatis_macro_driver::{embed_migrations, html_sql, py_sql, snake_name, sql, Table};
 
 pub mod plugin;
 
@@ -13,6 +13,8 @@ pub mod utils;
 pub mod executor;
 #[macro_use]
 pub mod crud;
+pub mod crud_traits;
+pub mod query;
 #[macro_use]
 pub mod error;
 pub mod decode;
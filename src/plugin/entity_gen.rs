@@ -0,0 +1,182 @@
+//! generate the rust entity structs of the existing tables, read by [`schema`](crate::schema).
+//!
+//! * a struct of the table with the serde derives and the `crud!`
+//! * the column types are mapped to the rust types and the `rbdc` types(`DateTime`,`Decimal`,`Json`,`Uuid`,`Bytes`...)
+//! * the nullable columns are `Option`
+//!
+//! ```rust
+//! use rbatis::executor::Executor;
+//! use rbatis::entity_gen;
+//!
+//! pub async fn print_entities(conn: &dyn Executor) -> Result<(), rbatis::Error> {
+//!     //all the tables, or `&["user".to_string()]`
+//!     let code = entity_gen::generate(conn, &[]).await?;
+//!     println!("{}", code);
+//!     Ok(())
+//! }
+//! ```
+use crate::decode::camel_to_snake;
+use crate::executor::Executor;
+use crate::schema::{self, TableInfo};
+use crate::Error;
+
+/// the rbdc types, they are imported by the generated code
+const RBDC_TYPES: [&str; 7] = [
+    "Bytes", "Date", "DateTime", "Decimal", "Json", "Time", "Uuid",
+];
+
+/// the strict and the reserved keywords of all the editions, except `self`, `crate` and `super`(not a raw identifier)
+const KEYWORDS: [&str; 48] = [
+    "abstract", "as", "async", "await", "become", "box", "break", "const", "continue", "do", "dyn",
+    "else", "enum", "extern", "false", "final", "fn", "for", "gen", "if", "impl", "in", "let", "loop",
+    "macro", "match", "mod", "move", "mut", "override", "priv", "pub", "ref", "return", "static",
+    "struct", "trait", "true", "try", "type", "typeof", "unsafe", "unsized", "use", "virtual", "where",
+    "while", "yield",
+];
+
+/// the code of `tables`, an empty `tables` is all the tables of the database
+pub async fn generate(executor: &dyn Executor, tables: &[String]) -> Result<String, Error> {
+    let driver_type = executor.driver_type()?.to_string();
+    let names = if tables.is_empty() {
+        schema::tables(executor).await?
+    } else {
+        tables.to_vec()
+    };
+    let mut infos = vec![];
+    for name in &names {
+        match schema::table_info(executor, name).await? {
+            Some(info) => infos.push(info),
+            None => return Err(Error::from(format!("table '{}' not exists", name))),
+        }
+    }
+    Ok(generate_code(&driver_type, &infos))
+}
+
+/// the code of the tables: the `use` of the rbdc types and the structs
+pub fn generate_code(driver_type: &str, tables: &[TableInfo]) -> String {
+    let structs: Vec<String> = tables
+        .iter()
+        .map(|t| generate_struct(driver_type, t))
+        .collect();
+    let used: Vec<&str> = RBDC_TYPES
+        .iter()
+        .copied()
+        .filter(|t| {
+            tables.iter().any(|table| {
+                table
+                    .columns
+                    .iter()
+                    .any(|c| rust_type(driver_type, &c.column_type) == *t)
+            })
+        })
+        .collect();
+    let mut code = String::new();
+    if used.len() == 1 {
+        code.push_str(&format!("use rbatis::rbdc::{};\n\n", used[0]));
+    } else if !used.is_empty() {
+        code.push_str(&format!("use rbatis::rbdc::{{{}}};\n\n", used.join(", ")));
+    }
+    code.push_str(&structs.join("\n"));
+    code
+}
+
+/// the struct and the `crud!` of a table
+pub fn generate_struct(driver_type: &str, table: &TableInfo) -> String {
+    let struct_name = struct_name(&table.name);
+    let mut code = String::new();
+    code.push_str("#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]\n");
+    code.push_str(&format!("pub struct {} {{\n", struct_name));
+    for column in &table.columns {
+        let field = field_name(&column.name);
+        if field.trim_start_matches("r#") != column.name {
+            code.push_str(&format!("    #[serde(rename = \"{}\")]\n", column.name));
+        }
+        let mut rust_type = rust_type(driver_type, &column.column_type).to_string();
+        if column.nullable {
+            rust_type = format!("Option<{}>", rust_type);
+        }
+        code.push_str(&format!("    pub {}: {},\n", field, rust_type));
+    }
+    code.push_str("}\n");
+    code.push_str(&format!(
+        "rbatis::crud!({} {{}}, \"{}\");\n",
+        struct_name, table.name
+    ));
+    code
+}
+
+/// the rust type of a column type, for example `VARCHAR(50)` => `String`, `decimal(10,2)` => `Decimal`.
+/// the unknown types are `rbs::Value`
+pub fn rust_type(driver_type: &str, column_type: &str) -> &'static str {
+    let t = column_type.trim().to_lowercase();
+    let unsigned = t.contains("unsigned");
+    let name = t
+        .split(['(', ' '])
+        .next()
+        .unwrap_or_default();
+    match name {
+        //the booleans of sqlite and mysql(`tinyint(1)`) are decoded as the integers
+        "bool" | "boolean" if driver_type == "sqlite" => "i64",
+        "bool" | "boolean" if driver_type == "mysql" => "i8",
+        "bool" | "boolean" | "bit" => "bool",
+        "tinyint" if unsigned || driver_type == "mssql" => "u8",
+        "tinyint" => "i8",
+        "smallint" | "int2" | "smallserial" if unsigned => "u16",
+        "smallint" | "int2" | "smallserial" => "i16",
+        "bigint" | "int8" | "bigserial" if unsigned => "u64",
+        "bigint" | "int8" | "bigserial" => "i64",
+        //the integers of sqlite are 64-bit
+        _ if driver_type == "sqlite" && name.contains("int") => "i64",
+        "int" | "integer" | "int4" | "mediumint" | "serial" if unsigned => "u32",
+        "int" | "integer" | "int4" | "mediumint" | "serial" => "i32",
+        "real" | "float4" if driver_type != "sqlite" => "f32",
+        "float" if driver_type == "mysql" => "f32",
+        "real" | "float" | "float8" | "double" => "f64",
+        "decimal" | "numeric" | "money" | "smallmoney" => "Decimal",
+        "date" => "Date",
+        "time" | "timetz" => "Time",
+        "datetime" | "datetime2" | "smalldatetime" | "datetimeoffset" | "timestamp"
+        | "timestamptz" => "DateTime",
+        "json" | "jsonb" => "Json",
+        "uuid" | "uniqueidentifier" => "Uuid",
+        "blob" | "tinyblob" | "mediumblob" | "longblob" | "bytea" | "binary" | "varbinary"
+        | "image" => "Bytes",
+        _ if name.contains("char") || name.contains("text") || name.contains("clob") => "String",
+        "enum" | "set" | "xml" | "" => "String",
+        _ => "rbs::Value",
+    }
+}
+
+/// `sys_user` => `SysUser`
+fn struct_name(table: &str) -> String {
+    let mut name = String::new();
+    for word in table.split(|c: char| !c.is_ascii_alphanumeric()) {
+        let mut chars = word.chars();
+        if let Some(c) = chars.next() {
+            name.push(c.to_ascii_uppercase());
+            name.push_str(chars.as_str());
+        }
+    }
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, 'T');
+    }
+    name
+}
+
+/// `createTime` => `create_time`, `type` => `r#type`, `self` => `self_`
+fn field_name(column: &str) -> String {
+    let mut name: String = camel_to_snake(column)
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+        name.insert(0, '_');
+    }
+    if KEYWORDS.contains(&name.as_str()) {
+        name.insert_str(0, "r#");
+    } else if matches!(name.as_str(), "self" | "crate" | "super") {
+        //can't be a raw identifier
+        name.push('_');
+    }
+    name
+}
@@ -0,0 +1,82 @@
+//! the id generators of the task ids and the primary keys.
+//!
+//! * [`Snowflake`] an `i64`, need the unique `machine_id`/`node_id` for every process
+//! * [`ObjectId`] a 24 chars hex string
+//! * [`UuidV7`] a time-ordered uuid string
+//! * [`Ulid`] a time-ordered 26 chars string
+//!
+//! the ids of the string generators are `Value::String`, a native uuid column(for example `uuid` of postgres)
+//! should be cast in the sql or use a string column.
+//!
+//! the empty primary key(`null` or `""`) of the crud insert is assigned by the generator of the table,
+//! set by the table name or by [`RBatis::set_table_id_generator`](crate::RBatis::set_table_id_generator)
+//! of `#[derive(rbatis::Table)]`
+//! ```rust
+//! use std::sync::Arc;
+//! use rbatis::RBatis;
+//! use rbatis::uuid_v7::UuidV7;
+//!
+//! let rb = RBatis::new();
+//! rb.set_id_generator("activity", "id", Arc::new(UuidV7::new()));
+//! ```
+use crate::object_id::ObjectId;
+use crate::snowflake::Snowflake;
+use rbs::Value;
+use std::fmt::Debug;
+use std::sync::Arc;
+
+pub trait IdGenerator: Send + Sync + Debug {
+    /// a new id, for example `Value::I64` or `Value::String`
+    fn generate_id(&self) -> Value;
+
+    /// a new id of `i64` for the task id of the executor, the string ids are hashed
+    fn generate_i64(&self) -> i64 {
+        match self.generate_id() {
+            Value::I64(v) => v,
+            Value::U64(v) => v as i64,
+            Value::I32(v) => v as i64,
+            Value::U32(v) => v as i64,
+            v => {
+                //FNV-1a
+                let mut hash: u64 = 0xcbf29ce484222325;
+                for b in v.as_str().unwrap_or_default().bytes() {
+                    hash ^= b as u64;
+                    hash = hash.wrapping_mul(0x100000001b3);
+                }
+                (hash & i64::MAX as u64) as i64
+            }
+        }
+    }
+}
+
+impl dyn IdGenerator {
+    /// the `generate()` of [`RBatis::task_id_generator`](crate::RBatis::task_id_generator) when it's an `Arc<Snowflake>`
+    #[deprecated(note = "please use generate_i64")]
+    pub fn generate(&self) -> i64 {
+        self.generate_i64()
+    }
+}
+
+impl IdGenerator for Snowflake {
+    fn generate_id(&self) -> Value {
+        Value::I64(self.generate())
+    }
+
+    fn generate_i64(&self) -> i64 {
+        self.generate()
+    }
+}
+
+/// an `ObjectId` is the generator of the new `ObjectId`s
+impl IdGenerator for ObjectId {
+    fn generate_id(&self) -> Value {
+        Value::String(ObjectId::new().to_hex())
+    }
+}
+
+/// the generator of the primary key `column` of a table, see [`RBatis::set_id_generator`](crate::RBatis::set_id_generator)
+#[derive(Debug, Clone)]
+pub struct TableIdGenerator {
+    pub column: String,
+    pub generator: Arc<dyn IdGenerator>,
+}
@@ -0,0 +1,422 @@
+//! versioned sql migrations.
+//!
+//! * a migration is a file `V{version}__{name}.sql`, for example `V1__create_user.sql`,`V2__add_user_email.sql`
+//! * the applied migrations are recorded into the table `rbatis_schema_history` with the checksum of the sql
+//! * the pending migrations are applied in the order of version by any `&dyn Executor`(a tx executor
+//!   of the caller), or every migration in a transaction if the database support the transactional ddl
+//!   (postgres,sqlite,mssql) by [`Migrations::run_with_tx`]
+//! * a version is locked by inserting the history row before the sql, two processes can't apply it both
+//! * refuse to run if the checksum of an applied migration is changed
+//!
+//! ```rust
+//! use rbatis::RBatis;
+//! use rbatis::migration::{Migration, Migrations};
+//!
+//! pub async fn migrate(rb: &RBatis) -> Result<(), rbatis::Error> {
+//!     //or Migrations::from_dir("migrations")?, or rbatis::embed_migrations!("migrations")
+//!     let migrations = Migrations::new(vec![
+//!         Migration::new(1, "create_user", "create table user (id integer primary key, name text);"),
+//!         Migration::new(2, "add_user_email", "alter table user add email text;"),
+//!     ])?;
+//!     //or migrations.run(&tx) in a transaction of the caller
+//!     let applied = migrations.run_with_tx(rb).await?;
+//!     println!("applied versions: {:?}", applied);
+//!     Ok(())
+//! }
+//! ```
+use crate::executor::Executor;
+use crate::rbatis::RBatis;
+use crate::{schema, Error};
+use rbs::Value;
+use std::path::Path;
+
+/// the table of the applied migrations
+pub const HISTORY_TABLE: &str = "rbatis_schema_history";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Migration {
+    pub version: u64,
+    pub name: String,
+    pub sql: String,
+    /// the checksum of `sql`, the line endings are ignored
+    pub checksum: String,
+}
+
+impl Migration {
+    pub fn new(version: u64, name: &str, sql: &str) -> Self {
+        Self {
+            version,
+            name: name.to_string(),
+            sql: sql.to_string(),
+            checksum: checksum(sql),
+        }
+    }
+
+    /// parse the file name `V{version}__{name}.sql`
+    pub fn parse_file_name(file_name: &str) -> Option<(u64, String)> {
+        let name = file_name.strip_prefix('V')?.strip_suffix(".sql")?;
+        let (version, name) = name.split_once("__")?;
+        Some((version.parse().ok()?, name.to_string()))
+    }
+}
+
+/// a row of `rbatis_schema_history`
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct MigrationHistory {
+    pub version: u64,
+    pub name: String,
+    pub checksum: String,
+    pub installed_at: String,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Migrations {
+    migrations: Vec<Migration>,
+}
+
+impl Migrations {
+    /// the migrations are sorted by version, the same version is an error
+    pub fn new(mut migrations: Vec<Migration>) -> Result<Self, Error> {
+        migrations.sort_by_key(|m| m.version);
+        for pair in migrations.windows(2) {
+            if pair[0].version == pair[1].version {
+                return Err(Error::from(format!(
+                    "duplicate migration version V{}: '{}' and '{}'",
+                    pair[0].version, pair[0].name, pair[1].name
+                )));
+            }
+        }
+        Ok(Self { migrations })
+    }
+
+    /// load the `V{version}__{name}.sql` files of `dir`, the other files are skipped
+    pub fn from_dir<P: AsRef<Path>>(dir: P) -> Result<Self, Error> {
+        let dir = dir.as_ref();
+        let entries = std::fs::read_dir(dir)
+            .map_err(|e| Error::from(format!("read dir {} fail: {}", dir.display(), e)))?;
+        let mut migrations = vec![];
+        for entry in entries {
+            let path = entry.map_err(|e| Error::from(e.to_string()))?.path();
+            let file_name = path
+                .file_name()
+                .and_then(|v| v.to_str())
+                .unwrap_or_default();
+            if let Some((version, name)) = Migration::parse_file_name(file_name) {
+                let sql = std::fs::read_to_string(&path)
+                    .map_err(|e| Error::from(format!("read {} fail: {}", path.display(), e)))?;
+                migrations.push(Migration::new(version, &name, &sql));
+            }
+        }
+        Self::new(migrations)
+    }
+
+    pub fn migrations(&self) -> &[Migration] {
+        &self.migrations
+    }
+
+    /// the applied migrations, empty if the history table not exists
+    pub async fn history(&self, executor: &dyn Executor) -> Result<Vec<MigrationHistory>, Error> {
+        if !history_exists(executor).await? {
+            return Ok(vec![]);
+        }
+        let sql = format!(
+            "select version, name, checksum, installed_at from {} order by version",
+            HISTORY_TABLE
+        );
+        crate::decode(executor.query(&sql, vec![]).await?)
+    }
+
+    /// the migrations not applied, error if the checksum of an applied migration is changed
+    /// or a pending migration is older than the applied ones
+    pub async fn pending(&self, executor: &dyn Executor) -> Result<Vec<&Migration>, Error> {
+        let history = self.history(executor).await?;
+        for applied in &history {
+            if let Some(m) = self.migrations.iter().find(|m| m.version == applied.version) {
+                if m.checksum != applied.checksum {
+                    return Err(Error::from(format!(
+                        "migration V{}__{} is changed after applied, checksum '{}' != '{}'",
+                        m.version, m.name, m.checksum, applied.checksum
+                    )));
+                }
+            }
+        }
+        let latest = history.last().map(|v| v.version).unwrap_or_default();
+        let mut pending = vec![];
+        for m in &self.migrations {
+            if history.iter().any(|v| v.version == m.version) {
+                continue;
+            }
+            if m.version < latest {
+                return Err(Error::from(format!(
+                    "migration V{}__{} is older than the applied V{}",
+                    m.version, m.name, latest
+                )));
+            }
+            pending.push(m);
+        }
+        Ok(pending)
+    }
+
+    /// apply the pending migrations on `executor` in the order of version and return the applied versions.
+    /// pass a tx executor to run them inside the transaction of the caller, it's committed by the caller,
+    /// or see [`Migrations::run_with_tx`] to commit every migration by itself.
+    ///
+    /// the history row of a version is inserted before the sql, so the other process applying
+    /// the same version at the same time fails on the primary key of `rbatis_schema_history`
+    pub async fn run(&self, executor: &dyn Executor) -> Result<Vec<u64>, Error> {
+        create_history(executor).await?;
+        let mut applied = vec![];
+        for m in self.pending(executor).await? {
+            apply(executor, m).await.map_err(|e| fail(m, e))?;
+            applied.push(m.version);
+        }
+        Ok(applied)
+    }
+
+    /// apply the pending migrations on one connection of `rb` and return the applied versions,
+    /// every migration is committed by itself if the ddl is transactional(postgres,sqlite,mssql)
+    pub async fn run_with_tx(&self, rb: &RBatis) -> Result<Vec<u64>, Error> {
+        let conn = rb.acquire().await?;
+        if !matches!(rb.driver_type()?, "postgres" | "sqlite" | "mssql") {
+            return self.run(&conn).await;
+        }
+        create_history(&conn).await?;
+        let pending = self.pending(&conn).await?;
+        let mut applied = vec![];
+        if pending.is_empty() {
+            return Ok(applied);
+        }
+        let mut tx = conn.begin().await?;
+        for (idx, m) in pending.into_iter().enumerate() {
+            if idx > 0 {
+                tx = tx.begin().await?;
+            }
+            if let Err(e) = apply(&tx, m).await {
+                let _ = tx.rollback().await;
+                return Err(fail(m, e));
+            }
+            tx.commit().await?;
+            applied.push(m.version);
+        }
+        Ok(applied)
+    }
+}
+
+fn fail(m: &Migration, e: Error) -> Error {
+    Error::from(format!("migration V{}__{} fail: {}", m.version, m.name, e))
+}
+
+async fn history_exists(executor: &dyn Executor) -> Result<bool, Error> {
+    Ok(schema::tables(executor)
+        .await?
+        .iter()
+        .any(|v| v.eq_ignore_ascii_case(HISTORY_TABLE)))
+}
+
+async fn create_history(executor: &dyn Executor) -> Result<(), Error> {
+    if history_exists(executor).await? {
+        return Ok(());
+    }
+    let sql = format!(
+        "create table {} (version BIGINT NOT NULL PRIMARY KEY, name VARCHAR(255) NOT NULL, checksum VARCHAR(64) NOT NULL, installed_at VARCHAR(64) NOT NULL)",
+        HISTORY_TABLE
+    );
+    if let Err(e) = executor.exec(&sql, vec![]).await {
+        //created by another process
+        if !history_exists(executor).await? {
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// insert the history row first(the lock of the version), then run the sql
+async fn apply(executor: &dyn Executor, m: &Migration) -> Result<(), Error> {
+    let version = Value::I64(m.version as i64);
+    let insert = executor
+        .exec(
+            &format!(
+                "insert into {} (version, name, checksum, installed_at) values (?,?,?,?)",
+                HISTORY_TABLE
+            ),
+            vec![
+                version.clone(),
+                Value::String(m.name.clone()),
+                Value::String(m.checksum.clone()),
+                Value::String(rbdc::DateTime::now().to_string()),
+            ],
+        )
+        .await;
+    if let Err(e) = insert {
+        let sql = format!("select count(1) as count from {} where version = ?", HISTORY_TABLE);
+        let applied = match executor.query(&sql, vec![version]).await {
+            Ok(v) => crate::decode::<u64>(v).unwrap_or_default() > 0,
+            Err(_) => false,
+        };
+        if applied {
+            return Err(Error::from("it's applied by another process"));
+        }
+        return Err(e);
+    }
+    for sql in split_sql(&m.sql) {
+        if let Err(e) = executor.exec(&sql, vec![]).await {
+            //the ddl of mysql is not rolled back, remove the history row to run it again
+            let _ = executor
+                .exec(
+                    &format!("delete from {} where version = ?", HISTORY_TABLE),
+                    vec![version],
+                )
+                .await;
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+/// the FNV-1a 64 hash of the sql, `\r\n` is the same as `\n`
+fn checksum(sql: &str) -> String {
+    let mut hash: u64 = 0xcbf29ce484222325;
+    for b in sql.replace("\r\n", "\n").bytes() {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    format!("{:016x}", hash)
+}
+
+/// split the statements by `;` and the mssql `GO` lines, the comments are removed.
+/// the `;` in the quotes, the postgres `$$`/`$tag$` bodies, the `BEGIN ... END` blocks
+/// (the sqlite triggers, the mysql/mssql procedures) and the comments are skipped
+pub fn split_sql(sql: &str) -> Vec<String> {
+    let mut statements = vec![];
+    let mut statement = String::new();
+    let chars: Vec<char> = sql.chars().collect();
+    let mut quote: Option<String> = None;
+    //the depth of the `BEGIN`/`CASE` ... `END` blocks
+    let mut depth = 0usize;
+    let mut idx = 0;
+    let mut push = |statement: &mut String| {
+        if !statement.trim().is_empty() {
+            statements.push(statement.trim().to_string());
+        }
+        statement.clear();
+    };
+    while idx < chars.len() {
+        let c = chars[idx];
+        let next = chars.get(idx + 1).copied().unwrap_or_default();
+        match quote.as_deref() {
+            Some(tag) if tag.starts_with('$') => {
+                if starts_with(&chars, idx, tag) {
+                    statement.push_str(tag);
+                    idx += tag.chars().count();
+                    quote = None;
+                    continue;
+                }
+            }
+            Some("'") if c == '\'' => quote = None,
+            Some("\"") if c == '"' => quote = None,
+            Some("`") if c == '`' => quote = None,
+            Some(_) => {}
+            None => match c {
+                '\'' => quote = Some("'".to_string()),
+                '"' => quote = Some("\"".to_string()),
+                '`' => quote = Some("`".to_string()),
+                '$' => {
+                    if let Some(tag) = dollar_tag(&chars, idx) {
+                        statement.push_str(&tag);
+                        idx += tag.chars().count();
+                        quote = Some(tag);
+                        continue;
+                    }
+                }
+                '-' if next == '-' => {
+                    while idx < chars.len() && chars[idx] != '\n' {
+                        idx += 1;
+                    }
+                    continue;
+                }
+                '/' if next == '*' => {
+                    idx += 2;
+                    while idx < chars.len() && !(chars[idx] == '*' && chars.get(idx + 1) == Some(&'/')) {
+                        idx += 1;
+                    }
+                    idx += 2;
+                    continue;
+                }
+                ';' if depth == 0 => {
+                    push(&mut statement);
+                    idx += 1;
+                    continue;
+                }
+                '\n' if depth == 0 => {
+                    //a line of `GO` is the batch separator of mssql
+                    let line_start = statement.rfind('\n').map(|v| v + 1).unwrap_or(0);
+                    if statement[line_start..].trim().eq_ignore_ascii_case("go") {
+                        statement.truncate(line_start);
+                        push(&mut statement);
+                        idx += 1;
+                        continue;
+                    }
+                }
+                c if c.is_ascii_alphabetic() || c == '_' => {
+                    let word = read_word(&chars, idx);
+                    if word.eq_ignore_ascii_case("begin") {
+                        //a `BEGIN`(`BEGIN TRANSACTION`) statement is not a block
+                        if !statement.trim().is_empty() {
+                            depth += 1;
+                        }
+                    } else if word.eq_ignore_ascii_case("case") {
+                        depth += 1;
+                    } else if word.eq_ignore_ascii_case("end") && depth > 0 {
+                        //`END IF`/`END LOOP`... close the blocks not counted
+                        let mut after = idx + word.len();
+                        while after < chars.len() && chars[after].is_whitespace() {
+                            after += 1;
+                        }
+                        let closed = read_word(&chars, after).to_ascii_lowercase();
+                        if !matches!(closed.as_str(), "if" | "loop" | "while" | "repeat") {
+                            depth -= 1;
+                        }
+                    }
+                    statement.push_str(&word);
+                    idx += word.len();
+                    continue;
+                }
+                _ => {}
+            },
+        }
+        statement.push(c);
+        idx += 1;
+    }
+    let line_start = statement.rfind('\n').map(|v| v + 1).unwrap_or(0);
+    if statement[line_start..].trim().eq_ignore_ascii_case("go") {
+        statement.truncate(line_start);
+    }
+    push(&mut statement);
+    statements
+}
+
+fn starts_with(chars: &[char], idx: usize, s: &str) -> bool {
+    s.chars()
+        .enumerate()
+        .all(|(i, c)| chars.get(idx + i) == Some(&c))
+}
+
+/// the postgres dollar quote `$$` or `$tag$` at `idx`, the `$1` parameters are not
+fn dollar_tag(chars: &[char], idx: usize) -> Option<String> {
+    let mut end = idx + 1;
+    while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_') {
+        end += 1;
+    }
+    if chars.get(end) != Some(&'$') || chars.get(idx + 1).map(|c| c.is_ascii_digit()) == Some(true) {
+        return None;
+    }
+    Some(chars[idx..=end].iter().collect())
+}
+
+/// the identifier(ascii) at `idx`
+fn read_word(chars: &[char], idx: usize) -> String {
+    chars[idx.min(chars.len())..]
+        .iter()
+        .take_while(|c| c.is_ascii_alphanumeric() || **c == '_')
+        .collect()
+}
@@ -1,9 +1,16 @@
+pub mod entity_gen;
+pub mod id_generator;
 pub mod intercept;
 pub mod intercept_log;
 pub mod intercept_page;
+pub mod migration;
 pub mod object_id;
 pub mod page;
+pub mod schema;
+pub mod segment;
 pub mod snowflake;
 pub mod table_sync;
+pub mod ulid;
+pub mod uuid_v7;
 
 pub use page::*;
@@ -0,0 +1,279 @@
+//! read the schema(tables, columns, primary keys, indexes and foreign keys) of the connected database.
+//!
+//! the sql depends on `driver_type()`:
+//! * sqlite: `sqlite_master` and the `pragma_*` table functions
+//! * mysql: `information_schema` of `database()`
+//! * postgres: `information_schema` and `pg_catalog` of `current_schema()`
+//! * mssql: `sys.*` of `schema_id()`
+//!
+//! ```rust
+//! use rbatis::executor::Executor;
+//! use rbatis::schema;
+//!
+//! pub async fn print_schema(conn: &dyn Executor) -> Result<(), rbatis::Error> {
+//!     for name in schema::tables(conn).await? {
+//!         if let Some(table) = schema::table_info(conn, &name).await? {
+//!             println!("{:?}", table);
+//!         }
+//!     }
+//!     Ok(())
+//! }
+//! ```
+use crate::executor::Executor;
+use crate::Error;
+use rbs::Value;
+
+/// a table and its columns, primary keys, indexes and foreign keys
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TableInfo {
+    pub name: String,
+    pub columns: Vec<ColumnInfo>,
+    /// the columns of the primary key in order
+    pub primary_keys: Vec<String>,
+    /// the indexes without the primary key
+    pub indexes: Vec<IndexInfo>,
+    pub foreign_keys: Vec<ForeignKeyInfo>,
+}
+
+impl TableInfo {
+    pub fn column(&self, name: &str) -> Option<&ColumnInfo> {
+        self.columns
+            .iter()
+            .find(|c| c.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ColumnInfo {
+    pub name: String,
+    /// the type declared in the database, for example `VARCHAR(50)`, `character varying(50)`, `TEXT`
+    pub column_type: String,
+    pub nullable: bool,
+    /// the default value expression
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct IndexInfo {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ForeignKeyInfo {
+    /// the constraint name, sqlite is the id of the foreign key
+    pub name: String,
+    pub columns: Vec<String>,
+    pub ref_table: String,
+    pub ref_columns: Vec<String>,
+    /// the delete rule, for example `CASCADE`, `SET NULL`. `None` is the default `NO ACTION`
+    pub on_delete: Option<String>,
+}
+
+/// the table names of the current database/schema
+pub async fn tables(executor: &dyn Executor) -> Result<Vec<String>, Error> {
+    let sql = match executor.driver_type()? {
+        "sqlite" => "select name from sqlite_master where type = 'table' and name not like 'sqlite_%' order by name",
+        "mysql" => "select table_name as name from information_schema.tables where table_schema = database() and table_type = 'BASE TABLE' order by table_name",
+        "postgres" => "select table_name as name from information_schema.tables where table_schema = current_schema() and table_type = 'BASE TABLE' order by table_name",
+        "mssql" => "select name from sys.tables where schema_id = schema_id() order by name",
+        driver => return Err(unsupported(driver)),
+    };
+    let rows = query_rows(executor, sql, vec![]).await?;
+    Ok(rows.iter().map(|row| as_string(&row["name"])).collect())
+}
+
+/// the columns of `table` in order, empty if the table not exists
+pub async fn columns(executor: &dyn Executor, table: &str) -> Result<Vec<ColumnInfo>, Error> {
+    let driver_type = executor.driver_type()?;
+    let sql = match driver_type {
+        "sqlite" => r#"select name, type, "notnull" = 0 as nullable, dflt_value as dflt from pragma_table_info(?) order by cid"#,
+        "mysql" => "select column_name as name, column_type as type, is_nullable as nullable, column_default as dflt from information_schema.columns where table_schema = database() and table_name = ? order by ordinal_position",
+        "postgres" => "select a.attname as name, format_type(a.atttypid, a.atttypmod) as type, not a.attnotnull as nullable, pg_get_expr(d.adbin, d.adrelid) as dflt from pg_attribute a left join pg_attrdef d on d.adrelid = a.attrelid and d.adnum = a.attnum where a.attrelid = to_regclass(?) and a.attnum > 0 and not a.attisdropped order by a.attnum",
+        "mssql" => "select c.name as name, t.name as type, c.max_length as max_length, c.precision as precision, c.scale as scale, c.is_nullable as nullable, dc.definition as dflt from sys.columns c join sys.types t on t.user_type_id = c.user_type_id left join sys.default_constraints dc on dc.object_id = c.default_object_id where c.object_id = object_id(?) order by c.column_id",
+        driver => return Err(unsupported(driver)),
+    };
+    let rows = query_rows(executor, sql, vec![Value::String(table.to_string())]).await?;
+    Ok(rows
+        .iter()
+        .map(|row| {
+            let mut column_type = as_string(&row["type"]);
+            if driver_type == "mssql" {
+                column_type = mssql_type(&column_type, row);
+            }
+            ColumnInfo {
+                name: as_string(&row["name"]),
+                column_type,
+                nullable: as_bool(&row["nullable"]),
+                default: match &row["dflt"] {
+                    Value::Null => None,
+                    v => Some(as_string(v)),
+                },
+            }
+        })
+        .collect())
+}
+
+/// the primary key columns of `table` in order
+pub async fn primary_keys(executor: &dyn Executor, table: &str) -> Result<Vec<String>, Error> {
+    let sql = match executor.driver_type()? {
+        "sqlite" => "select name from pragma_table_info(?) where pk > 0 order by pk",
+        "mysql" => "select column_name as name from information_schema.key_column_usage where table_schema = database() and table_name = ? and constraint_name = 'PRIMARY' order by ordinal_position",
+        "postgres" => "select a.attname as name from pg_index i join pg_attribute a on a.attrelid = i.indrelid and a.attnum = any(i.indkey) where i.indrelid = to_regclass(?) and i.indisprimary order by array_position(i.indkey::int2[], a.attnum)",
+        "mssql" => "select c.name as name from sys.indexes i join sys.index_columns ic on ic.object_id = i.object_id and ic.index_id = i.index_id join sys.columns c on c.object_id = ic.object_id and c.column_id = ic.column_id where i.object_id = object_id(?) and i.is_primary_key = 1 order by ic.key_ordinal",
+        driver => return Err(unsupported(driver)),
+    };
+    let rows = query_rows(executor, sql, vec![Value::String(table.to_string())]).await?;
+    Ok(rows.iter().map(|row| as_string(&row["name"])).collect())
+}
+
+/// the indexes(and unique constraints) of `table`, without the primary key
+pub async fn indexes(executor: &dyn Executor, table: &str) -> Result<Vec<IndexInfo>, Error> {
+    let sql = match executor.driver_type()? {
+        "sqlite" => r#"select il.name as name, il."unique" as is_unique, ii.name as column_name from pragma_index_list(?) il join pragma_index_info(il.name) ii where il.origin != 'pk' order by il.name, ii.seqno"#,
+        "mysql" => "select index_name as name, non_unique = 0 as is_unique, column_name as column_name from information_schema.statistics where table_schema = database() and table_name = ? and index_name != 'PRIMARY' order by index_name, seq_in_index",
+        "postgres" => "select c.relname as name, i.indisunique as is_unique, a.attname as column_name from pg_index i join pg_class c on c.oid = i.indexrelid join pg_attribute a on a.attrelid = i.indrelid and a.attnum = any(i.indkey) where i.indrelid = to_regclass(?) and not i.indisprimary order by c.relname, array_position(i.indkey::int2[], a.attnum)",
+        "mssql" => "select i.name as name, i.is_unique as is_unique, c.name as column_name from sys.indexes i join sys.index_columns ic on ic.object_id = i.object_id and ic.index_id = i.index_id join sys.columns c on c.object_id = ic.object_id and c.column_id = ic.column_id where i.object_id = object_id(?) and i.is_primary_key = 0 and i.type > 0 and ic.is_included_column = 0 order by i.name, ic.key_ordinal",
+        driver => return Err(unsupported(driver)),
+    };
+    let rows = query_rows(executor, sql, vec![Value::String(table.to_string())]).await?;
+    let mut indexes: Vec<IndexInfo> = vec![];
+    for row in &rows {
+        let name = as_string(&row["name"]);
+        let column = as_string(&row["column_name"]);
+        match indexes.last_mut() {
+            Some(index) if index.name == name => index.columns.push(column),
+            _ => indexes.push(IndexInfo {
+                name,
+                columns: vec![column],
+                unique: as_bool(&row["is_unique"]),
+            }),
+        }
+    }
+    Ok(indexes)
+}
+
+/// the foreign keys of `table`
+pub async fn foreign_keys(
+    executor: &dyn Executor,
+    table: &str,
+) -> Result<Vec<ForeignKeyInfo>, Error> {
+    let sql = match executor.driver_type()? {
+        "sqlite" => r#"select id as name, "from" as column_name, "table" as ref_table, "to" as ref_column, on_delete from pragma_foreign_key_list(?) order by id, seq"#,
+        "mysql" => "select k.constraint_name as name, k.column_name as column_name, k.referenced_table_name as ref_table, k.referenced_column_name as ref_column, rc.delete_rule as on_delete from information_schema.key_column_usage k join information_schema.referential_constraints rc on rc.constraint_schema = k.table_schema and rc.constraint_name = k.constraint_name where k.table_schema = database() and k.table_name = ? and k.referenced_table_name is not null order by k.constraint_name, k.ordinal_position",
+        "postgres" => "select con.conname as name, a.attname as column_name, cf.relname as ref_table, af.attname as ref_column, case con.confdeltype when 'r' then 'RESTRICT' when 'c' then 'CASCADE' when 'n' then 'SET NULL' when 'd' then 'SET DEFAULT' else 'NO ACTION' end as on_delete from pg_constraint con join pg_class cf on cf.oid = con.confrelid cross join lateral unnest(con.conkey, con.confkey) with ordinality as k(col, ref_col, idx) join pg_attribute a on a.attrelid = con.conrelid and a.attnum = k.col join pg_attribute af on af.attrelid = con.confrelid and af.attnum = k.ref_col where con.conrelid = to_regclass(?) and con.contype = 'f' order by con.conname, k.idx",
+        "mssql" => "select fk.name as name, c.name as column_name, rt.name as ref_table, rc.name as ref_column, fk.delete_referential_action_desc as on_delete from sys.foreign_keys fk join sys.foreign_key_columns fkc on fkc.constraint_object_id = fk.object_id join sys.columns c on c.object_id = fkc.parent_object_id and c.column_id = fkc.parent_column_id join sys.tables rt on rt.object_id = fkc.referenced_object_id join sys.columns rc on rc.object_id = fkc.referenced_object_id and rc.column_id = fkc.referenced_column_id where fk.parent_object_id = object_id(?) order by fk.name, fkc.constraint_column_id",
+        driver => return Err(unsupported(driver)),
+    };
+    let rows = query_rows(executor, sql, vec![Value::String(table.to_string())]).await?;
+    let mut foreign_keys: Vec<ForeignKeyInfo> = vec![];
+    for row in &rows {
+        let name = as_string(&row["name"]);
+        let column = as_string(&row["column_name"]);
+        let ref_column = as_string(&row["ref_column"]);
+        match foreign_keys.last_mut() {
+            Some(fk) if fk.name == name => {
+                fk.columns.push(column);
+                fk.ref_columns.push(ref_column);
+            }
+            _ => foreign_keys.push(ForeignKeyInfo {
+                name,
+                columns: vec![column],
+                ref_table: as_string(&row["ref_table"]),
+                ref_columns: vec![ref_column],
+                on_delete: delete_rule(&as_string(&row["on_delete"])),
+            }),
+        }
+    }
+    Ok(foreign_keys)
+}
+
+/// all the info of `table`, `None` if the table not exists
+pub async fn table_info(executor: &dyn Executor, table: &str) -> Result<Option<TableInfo>, Error> {
+    let columns = columns(executor, table).await?;
+    if columns.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(TableInfo {
+        name: table.to_string(),
+        columns,
+        primary_keys: primary_keys(executor, table).await?,
+        indexes: indexes(executor, table).await?,
+        foreign_keys: foreign_keys(executor, table).await?,
+    }))
+}
+
+async fn query_rows(
+    executor: &dyn Executor,
+    sql: &str,
+    args: Vec<Value>,
+) -> Result<Vec<Value>, Error> {
+    match executor.query(sql, args).await? {
+        Value::Array(rows) => Ok(rows),
+        _ => Ok(vec![]),
+    }
+}
+
+fn unsupported(driver_type: &str) -> Error {
+    Error::from(format!(
+        "schema introspection not support driver '{}'",
+        driver_type
+    ))
+}
+
+fn as_string(v: &Value) -> String {
+    match v {
+        Value::Null => String::new(),
+        Value::String(s) => s.to_string(),
+        Value::Binary(b) => String::from_utf8_lossy(b).to_string(),
+        Value::Ext(_, v) => as_string(v),
+        v => v.to_string(),
+    }
+}
+
+/// `SET_NULL`(mssql) => `SET NULL`, the default `NO ACTION` is `None`
+fn delete_rule(rule: &str) -> Option<String> {
+    let rule = rule.trim().replace('_', " ").to_uppercase();
+    if rule.is_empty() || rule == "NO ACTION" {
+        return None;
+    }
+    Some(rule)
+}
+
+/// `true`/`1`/`YES`
+fn as_bool(v: &Value) -> bool {
+    match v {
+        Value::Bool(b) => *b,
+        Value::String(s) => s.eq_ignore_ascii_case("yes") || s == "1" || s.eq_ignore_ascii_case("true"),
+        Value::Ext(_, v) => as_bool(v),
+        v => v.as_i64().unwrap_or_default() != 0,
+    }
+}
+
+/// `sys.types` has no length, for example `nvarchar` + `max_length = 100` => `nvarchar(50)`
+fn mssql_type(name: &str, row: &Value) -> String {
+    let max_length = row["max_length"].as_i64().unwrap_or_default();
+    let length = |len: i64| {
+        if len == -1 {
+            "max".to_string()
+        } else {
+            len.to_string()
+        }
+    };
+    match name {
+        "varchar" | "char" | "varbinary" | "binary" => format!("{}({})", name, length(max_length)),
+        "nvarchar" | "nchar" => format!(
+            "{}({})",
+            name,
+            length(if max_length == -1 { -1 } else { max_length / 2 })
+        ),
+        "decimal" | "numeric" => format!(
+            "{}({},{})",
+            name,
+            row["precision"].as_i64().unwrap_or_default(),
+            row["scale"].as_i64().unwrap_or_default()
+        ),
+        _ => name.to_string(),
+    }
+}
@@ -0,0 +1,220 @@
+//! the segment id generator(like the Leaf segment of meituan), the ids are reserved by ranges from the table
+//! `rbatis_id_segment`, so the processes don't need an unique `machine_id` like [`Snowflake`](crate::snowflake::Snowflake).
+//!
+//! * a range is reserved by `max_id = max_id + step` with the compare and set, every `biz_tag` is a row
+//! * the next range is reserved in the background when 10% of the current range is used
+//! * the ids are monotonic across the restarts, the unused ids of a range are skipped
+//!
+//! ```rust
+//! use rbatis::RBatis;
+//! use rbatis::segment::Segment;
+//!
+//! pub async fn new_order_id(rb: &RBatis) -> Result<i64, rbatis::Error> {
+//!     //keep the segment for the process, for example in a `LazyLock`
+//!     let segment = Segment::new(rb, "order", 1000);
+//!     segment.next_id().await
+//! }
+//! ```
+use crate::executor::Executor;
+use crate::rbatis::RBatis;
+use crate::{schema, Error};
+use futures::lock::Mutex;
+use rbs::Value;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// the table of the segments
+pub const SEGMENT_TABLE: &str = "rbatis_id_segment";
+
+/// the times to retry when the reserve is conflicted with the other processes
+const MAX_RETRY: usize = 16;
+
+/// the ids `(id, max]` reserved from the table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SegmentRange {
+    /// the last id, the next id is `id + 1`
+    pub id: i64,
+    pub max: i64,
+    pub step: i64,
+}
+
+impl SegmentRange {
+    pub fn remaining(&self) -> i64 {
+        self.max - self.id
+    }
+}
+
+/// the double buffered segment of a `biz_tag`, the clones share the ranges
+#[derive(Clone)]
+pub struct Segment {
+    inner: Arc<SegmentInner>,
+}
+
+struct SegmentInner {
+    rb: RBatis,
+    biz_tag: String,
+    step: i64,
+    state: Mutex<SegmentState>,
+    loading: AtomicBool,
+}
+
+#[derive(Default)]
+struct SegmentState {
+    current: SegmentRange,
+    next: Option<SegmentRange>,
+}
+
+impl Segment {
+    /// `step` is the range size of a new `biz_tag`, the step of an existing row is read from the table
+    pub fn new(rb: &RBatis, biz_tag: &str, step: i64) -> Self {
+        Self {
+            inner: Arc::new(SegmentInner {
+                rb: rb.clone(),
+                biz_tag: biz_tag.to_string(),
+                step: step.max(1),
+                state: Mutex::new(SegmentState::default()),
+                loading: AtomicBool::new(false),
+            }),
+        }
+    }
+
+    pub fn biz_tag(&self) -> &str {
+        &self.inner.biz_tag
+    }
+
+    pub async fn next_id(&self) -> Result<i64, Error> {
+        let inner = &self.inner;
+        let mut state = inner.state.lock().await;
+        if state.current.remaining() <= 0 {
+            state.current = match state.next.take() {
+                Some(next) => next,
+                None => reserve(&inner.rb, &inner.biz_tag, inner.step).await?,
+            };
+        }
+        state.current.id += 1;
+        let id = state.current.id;
+        let used = state.current.step - state.current.remaining();
+        if state.next.is_none()
+            && used * 10 >= state.current.step
+            && !inner.loading.swap(true, Ordering::SeqCst)
+        {
+            let inner = inner.clone();
+            rbdc::rt::spawn(async move {
+                match reserve(&inner.rb, &inner.biz_tag, inner.step).await {
+                    Ok(next) => {
+                        let mut state = inner.state.lock().await;
+                        //a range reserved by `next_id` at the same time is newer
+                        if state.next.is_none() && next.id >= state.current.max {
+                            state.next = Some(next);
+                        }
+                    }
+                    Err(e) => {
+                        log::warn!("segment '{}' reserve fail: {}", inner.biz_tag, e);
+                    }
+                }
+                inner.loading.store(false, Ordering::SeqCst);
+            });
+        }
+        Ok(id)
+    }
+}
+
+/// reserve the next range of `biz_tag`, the table and the row are created if not exists
+pub async fn reserve(
+    executor: &dyn Executor,
+    biz_tag: &str,
+    step: i64,
+) -> Result<SegmentRange, Error> {
+    let select = format!("select max_id, step from {} where biz_tag = ?", SEGMENT_TABLE);
+    for _ in 0..MAX_RETRY {
+        let rows = match executor
+            .query(&select, vec![Value::String(biz_tag.to_string())])
+            .await
+        {
+            Ok(rows) => rows,
+            Err(e) => {
+                if table_exists(executor).await? {
+                    return Err(e);
+                }
+                create_table(executor).await?;
+                continue;
+            }
+        };
+        let row = match rows.as_array().and_then(|v| v.first()) {
+            Some(row) => row,
+            None => {
+                //the insert of another process is conflicted, read it again
+                let _ = executor
+                    .exec(
+                        &format!(
+                            "insert into {} (biz_tag, max_id, step, update_time) values (?,?,?,?)",
+                            SEGMENT_TABLE
+                        ),
+                        vec![
+                            Value::String(biz_tag.to_string()),
+                            Value::I64(0),
+                            Value::I64(step),
+                            Value::String(rbdc::DateTime::now().to_string()),
+                        ],
+                    )
+                    .await;
+                continue;
+            }
+        };
+        let max_id = as_i64(&row["max_id"]);
+        let step = as_i64(&row["step"]).max(1);
+        let result = executor
+            .exec(
+                &format!(
+                    "update {} set max_id = ?, update_time = ? where biz_tag = ? and max_id = ?",
+                    SEGMENT_TABLE
+                ),
+                vec![
+                    Value::I64(max_id + step),
+                    Value::String(rbdc::DateTime::now().to_string()),
+                    Value::String(biz_tag.to_string()),
+                    Value::I64(max_id),
+                ],
+            )
+            .await?;
+        if result.rows_affected == 1 {
+            return Ok(SegmentRange {
+                id: max_id,
+                max: max_id + step,
+                step,
+            });
+        }
+    }
+    Err(Error::from(format!(
+        "segment '{}' reserve fail, conflicted {} times",
+        biz_tag, MAX_RETRY
+    )))
+}
+
+async fn table_exists(executor: &dyn Executor) -> Result<bool, Error> {
+    Ok(schema::tables(executor)
+        .await?
+        .iter()
+        .any(|v| v.eq_ignore_ascii_case(SEGMENT_TABLE)))
+}
+
+async fn create_table(executor: &dyn Executor) -> Result<(), Error> {
+    let sql = format!(
+        "create table {} (biz_tag VARCHAR(128) NOT NULL PRIMARY KEY, max_id BIGINT NOT NULL, step BIGINT NOT NULL, update_time VARCHAR(64) NOT NULL)",
+        SEGMENT_TABLE
+    );
+    if let Err(e) = executor.exec(&sql, vec![]).await {
+        //created by another process
+        if !table_exists(executor).await? {
+            return Err(e);
+        }
+    }
+    Ok(())
+}
+
+fn as_i64(v: &Value) -> i64 {
+    match v {
+        Value::String(s) => s.parse().unwrap_or_default(),
+        v => v.as_i64().unwrap_or_default(),
+    }
+}
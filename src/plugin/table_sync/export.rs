@@ -0,0 +1,144 @@
+use crate::executor::Executor;
+use crate::table_sync::{ColumnMapper, TableDef};
+use crate::{schema, Error};
+use rbs::Value;
+use std::collections::{BTreeSet, HashMap};
+
+/// the ddl script of the tables for the dialect of `mapper`, it's stable for the same input,
+/// so the script can be checked into the repository and diffed.
+///
+/// the tables are sorted by the foreign keys(the referenced table first), otherwise keep the order.
+/// the foreign keys of a cycle(`a` references `b`, `b` references `a`) are added at the end by
+/// `ALTER TABLE ... ADD CONSTRAINT ...`, except sqlite which creates them with the table
+/// ```rust
+/// use rbatis::table_sync::{export, SqliteTableMapper, TableDef};
+///
+/// let user = rbs::value!{"id": "INTEGER", "name": "TEXT"};
+/// let script = export(&SqliteTableMapper{}, &[(TableDef::new("user").primary_key(&["id"]), user)]).unwrap();
+/// assert_eq!(script, "-- sqlite schema, generated by rbatis\n\nCREATE TABLE user (id INTEGER,name TEXT,PRIMARY KEY (id));\n");
+/// ```
+pub fn export(mapper: &dyn ColumnMapper, tables: &[(TableDef, Value)]) -> Result<String, Error> {
+    let mut script = format!("-- {} schema, generated by rbatis\n", mapper.driver_type());
+    //sqlite can't add a foreign key to an existing table, but a table can reference a table not created yet
+    let sqlite = mapper.driver_type() == "sqlite";
+    let mut alters = vec![];
+    for ((def, table), deferred) in sort_by_foreign_keys(tables) {
+        let table = match table {
+            Value::Map(m) => m,
+            _ => {
+                return Err(Error::from(format!(
+                    "table '{}' not is an struct or map!",
+                    def.name
+                )))
+            }
+        };
+        let mut def = def.clone();
+        if !sqlite && !deferred.is_empty() {
+            let mut index = 0;
+            def.foreign_keys.retain(|fk| {
+                let keep = !deferred.contains(&index);
+                if !keep {
+                    alters.push(format!("ALTER TABLE {} ADD {};", def.name, fk.sql()));
+                }
+                index += 1;
+                keep
+            });
+        }
+        script.push('\n');
+        for sql in def.create_sql(mapper, table) {
+            script.push_str(&sql);
+            script.push('\n');
+        }
+    }
+    if !alters.is_empty() {
+        script.push('\n');
+        for sql in alters {
+            script.push_str(&sql);
+            script.push('\n');
+        }
+    }
+    Ok(script)
+}
+
+/// the ddl script of the tables of the connected database(empty `tables` is all the tables),
+/// the column types are kept as is, so `mapper` should be the same dialect
+pub async fn export_schema(
+    executor: &dyn Executor,
+    mapper: &dyn ColumnMapper,
+    tables: &[String],
+) -> Result<String, Error> {
+    let names = if tables.is_empty() {
+        schema::tables(executor).await?
+    } else {
+        tables.to_vec()
+    };
+    let mut defs = vec![];
+    for name in &names {
+        match schema::table_info(executor, name).await? {
+            Some(info) => defs.push(TableDef::from_info(&info)),
+            None => return Err(Error::from(format!("table '{}' not exists", name))),
+        }
+    }
+    export(mapper, &defs)
+}
+
+/// the referenced tables first(the smallest index of the ready tables), with the indexes of the deferred
+/// foreign keys of every table. a cycle is broken at its first table(by the index), the foreign keys of
+/// that table to the tables not created yet are deferred
+fn sort_by_foreign_keys(tables: &[(TableDef, Value)]) -> Vec<(&(TableDef, Value), Vec<usize>)> {
+    let index: HashMap<String, usize> = tables
+        .iter()
+        .enumerate()
+        .map(|(i, (def, _))| (def.name.to_lowercase(), i))
+        .collect();
+    //the referenced tables of every table, and the tables referencing every table
+    let mut depends: Vec<Vec<usize>> = vec![vec![]; tables.len()];
+    let mut referenced_by: Vec<Vec<usize>> = vec![vec![]; tables.len()];
+    for (i, (def, _)) in tables.iter().enumerate() {
+        for fk in &def.foreign_keys {
+            if let Some(&j) = index.get(&fk.ref_table.to_lowercase()) {
+                if j != i && !depends[i].contains(&j) {
+                    depends[i].push(j);
+                    referenced_by[j].push(i);
+                }
+            }
+        }
+    }
+    let mut waiting: Vec<usize> = depends.iter().map(|v| v.len()).collect();
+    let mut created = vec![false; tables.len()];
+    let mut ready: BTreeSet<usize> = (0..tables.len()).filter(|i| waiting[*i] == 0).collect();
+    let mut sorted = Vec::with_capacity(tables.len());
+    let mut next = 0;
+    while sorted.len() < tables.len() {
+        let i = match ready.pop_first() {
+            Some(i) => i,
+            None => {
+                //a cycle
+                while created[next] {
+                    next += 1;
+                }
+                next
+            }
+        };
+        let (def, _) = &tables[i];
+        let deferred: Vec<usize> = def
+            .foreign_keys
+            .iter()
+            .enumerate()
+            .filter(|(_, fk)| match index.get(&fk.ref_table.to_lowercase()) {
+                Some(&j) => j != i && !created[j],
+                None => false,
+            })
+            .map(|(k, _)| k)
+            .collect();
+        created[i] = true;
+        for &j in &referenced_by[i] {
+            waiting[j] -= 1;
+            if waiting[j] == 0 && !created[j] {
+                ready.insert(j);
+            }
+        }
+        sorted.push((&tables[i], deferred));
+    }
+    sorted
+}
@@ -1,31 +1,38 @@
+pub mod export;
 pub mod mssql_mapper;
 pub mod mysql_mapper;
+pub mod override_mapper;
 pub mod pg_mapper;
 pub mod sqlite_mapper;
+pub mod table_def;
 
 use crate::executor::Executor;
 use crate::Error;
 use futures_core::future::BoxFuture;
-use log::debug;
+pub use export::*;
 pub use mssql_mapper::*;
 pub use mysql_mapper::*;
+pub use override_mapper::*;
 pub use pg_mapper::*;
 use rbs::Value;
 pub use sqlite_mapper::*;
+pub use table_def::*;
 
-const PRIMARY_KEY: &'static str = " PRIMARY KEY ";
+pub(crate) const PRIMARY_KEY: &'static str = " PRIMARY KEY ";
 
-/// create table if not exists, add column if not exists
+/// create table if not exists, add the columns not exists and report the columns of a different type.
+/// use [`TableDef`] to declare the primary key, indexes and foreign keys.
+/// the existing table is read by [`schema::columns`](crate::schema::columns)
 /// ```rust
 /// use rbatis::executor::{Executor, RBatisConnExecutor};
 /// use rbatis::RBatis;
 /// use rbatis::table_sync::{MysqlTableMapper, SqliteTableMapper, sync};
-/// use rbs::to_value;
+/// use rbs::value;
 ///
 /// /// let rb = RBatis::new();
 /// /// let conn = rb.acquire().await;
 /// pub async fn do_sync_table(conn: &dyn Executor){
-///     let map = rbs::to_value!{
+///     let map = rbs::value!{
 ///             "id":"TEXT",
 ///             "name":"TEXT",
 ///      };
@@ -39,7 +46,7 @@ const PRIMARY_KEY: &'static str = " PRIMARY KEY ";
 /// use rbatis::executor::{Executor, RBatisConnExecutor};
 /// use rbatis::RBatis;
 /// use rbatis::table_sync::{MysqlTableMapper, SqliteTableMapper, sync};
-/// use rbs::to_value;
+/// use rbs::value;
 ///
 /// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 /// pub struct User{
@@ -51,7 +58,7 @@ const PRIMARY_KEY: &'static str = " PRIMARY KEY ";
 /// /// let conn = rb.acquire().await;
 /// pub async fn do_sync_table(conn: &dyn Executor){
 ///      let table = User{id: "".to_string(), name: Some("".to_string())};
-///      let _ = sync(conn, &SqliteTableMapper{},to_value!(table),"user").await;
+///      let _ = sync(conn, &SqliteTableMapper{},value!(table),"user").await;
 /// }
 ///
 /// ```
@@ -61,7 +68,7 @@ const PRIMARY_KEY: &'static str = " PRIMARY KEY ";
 /// use rbatis::executor::Executor;
 /// use rbatis::RBatis;
 /// use rbatis::table_sync::{MysqlTableMapper, sync};
-/// use rbs::to_value;
+/// use rbs::value;
 ///
 /// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 /// pub struct User{
@@ -71,7 +78,7 @@ const PRIMARY_KEY: &'static str = " PRIMARY KEY ";
 ///
 /// pub async fn do_sync_table_mysql(conn: &dyn Executor){
 ///      let table = User{id: "".to_string(), name: Some("VARCHAR(50)".to_string())};
-///      let _ = sync(conn, &MysqlTableMapper{},to_value!(table),"user").await;
+///      let _ = sync(conn, &MysqlTableMapper{},value!(table),"user").await;
 /// }
 /// ```
 pub fn sync<'a>(
@@ -79,78 +86,121 @@ pub fn sync<'a>(
     mapper: &'a dyn ColumnMapper,
     table: Value,
     table_name: &str,
-) -> BoxFuture<'a, Result<(), Error>> {
-    let name = table_name.to_owned();
-    Box::pin(async move {
-        match table {
-            Value::Map(m) => {
-                let db_driver_type = executor.driver_type()?;
-                if db_driver_type != mapper.driver_type() {
-                    return Err(Error::from(format!(
-                        "table sync mapper driver='{}',db driver='{}'",
-                        mapper.driver_type(),
-                        db_driver_type
-                    )));
-                }
-                let mut sql_create = format!("CREATE TABLE {} ", name);
-                let mut sql_column = format!("");
-                for (k, v) in &m {
-                    let k = k.as_str().unwrap_or_default();
-                    let column_type_value = mapper.get_column_type(k, &v);
-                    sql_column.push_str(k);
-                    sql_column.push_str(" ");
-                    sql_column.push_str(column_type_value.as_str());
-                    if column_type_value.is_empty() && k.eq("id")
-                        || v.as_str().unwrap_or_default() == "id"
-                    {
-                        sql_column.push_str(&PRIMARY_KEY);
-                    }
-                    sql_column.push_str(",");
-                }
-                if sql_column.ends_with(",") {
-                    sql_column = sql_column.trim_end_matches(",").to_string();
-                }
-                sql_create = sql_create + &format!("({});", sql_column);
-                let result_create = executor.exec(&sql_create, vec![]).await;
-                match result_create {
-                    Ok(_) => {}
-                    Err(e) => {
-                        if e.to_string().to_lowercase().contains("already") {
-                            //TODO have any better way do not Repeated add and compatibility with most databases
-                            for (k, v) in &m {
-                                let k = k.as_str().unwrap_or_default();
-                                let mut id_key = "";
-                                if k.eq("id") || v.as_str().unwrap_or_default() == "id" {
-                                    id_key = &PRIMARY_KEY;
-                                }
-                                let column_type = mapper.get_column_type(k, &v);
-                                match executor
-                                    .exec(
-                                        &format!(
-                                            "alter table {} add {} {} {};",
-                                            name, k, column_type, id_key
-                                        ),
-                                        vec![],
-                                    )
-                                    .await
-                                {
-                                    Ok(_) => {}
-                                    Err(e) => {
-                                        debug!("ADD COLUMN fail={}", e);
-                                        continue;
-                                    }
-                                }
-                            }
-                            return Ok(());
-                        }
-                        return Err(e);
-                    }
-                }
-                Ok(())
-            }
-            _ => Err(Error::from("table not is an struct or map!")),
+) -> BoxFuture<'a, Result<SyncReport, Error>> {
+    let def = TableDef::new(table_name);
+    Box::pin(async move { def.sync(executor, mapper, table).await })
+}
+
+/// the dry run of [`sync`], only read the existing table and return the report with the ddl not executed.
+/// ```rust
+/// use rbatis::executor::Executor;
+/// use rbatis::table_sync::{plan, SqliteTableMapper};
+///
+/// pub async fn print_plan(conn: &dyn Executor) -> Result<(), rbatis::Error> {
+///     let table = rbs::value!{"id": "INTEGER", "name": "TEXT"};
+///     let report = plan(conn, &SqliteTableMapper{}, table, "user").await?;
+///     for sql in &report.ddl {
+///         println!("{}", sql);
+///     }
+///     Ok(())
+/// }
+/// ```
+pub fn plan<'a>(
+    executor: &'a dyn Executor,
+    mapper: &'a dyn ColumnMapper,
+    table: Value,
+    table_name: &str,
+) -> BoxFuture<'a, Result<SyncReport, Error>> {
+    let def = TableDef::new(table_name);
+    Box::pin(async move { def.plan(executor, mapper, table).await })
+}
+
+/// the result of [`sync`] and [`plan`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SyncReport {
+    pub table: String,
+    /// the table not exists and is created
+    pub created: bool,
+    /// the columns created or added by `alter table add`
+    pub added: Vec<String>,
+    /// the columns exist with the same type
+    pub skipped: Vec<String>,
+    /// the columns exist but the type is different, they are not changed
+    pub mismatched: Vec<ColumnMismatch>,
+    /// the ddl executed by [`sync`], or planned by [`plan`]
+    pub ddl: Vec<String>,
+    /// the declared constraints of [`TableDef`] can't be applied to the existing table
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnMismatch {
+    pub column: String,
+    /// the type of the mapper
+    pub expected: String,
+    /// the type of the database
+    pub actual: String,
+}
+
+/// compare the type of the mapper with the type of the database,
+/// the case, the constraints(`NOT NULL`,`PRIMARY KEY`...) and the aliases(`INT8` = `BIGINT`) are ignored.
+/// the length is only compared if both have it, for example `DECIMAL` = `decimal(10,2)`
+pub fn is_same_type(expected: &str, actual: &str) -> bool {
+    if expected.trim().is_empty() {
+        return true;
+    }
+    let (expected_name, expected_args) = normalize_type(expected);
+    let (actual_name, actual_args) = normalize_type(actual);
+    if expected_name != actual_name {
+        return false;
+    }
+    expected_args.is_empty() || actual_args.is_empty() || expected_args == actual_args
+}
+
+/// `" Character Varying (50) NOT NULL"` => `("varchar", "50")`
+fn normalize_type(column_type: &str) -> (String, String) {
+    let mut t = column_type
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ");
+    for constraint in [
+        " not null", " null", " primary key", " default ", " unique", " references ",
+        " auto_increment", " autoincrement", " identity", " check", " generated ", " collate ",
+        " unsigned", " comment ",
+    ] {
+        if let Some(idx) = t.find(constraint) {
+            t.truncate(idx);
         }
-    })
+    }
+    let (name, args) = match t.find('(') {
+        Some(idx) => (
+            t[..idx].trim().to_string(),
+            t[idx + 1..].trim_end_matches(')').replace(' ', ""),
+        ),
+        None => (t.trim().to_string(), String::new()),
+    };
+    let name = match name.as_str() {
+        "int8" | "bigint" | "bigserial" => "bigint",
+        "int" | "int4" | "integer" | "mediumint" | "serial" => "integer",
+        "int2" | "smallint" => "smallint",
+        "bool" | "boolean" => "boolean",
+        "float8" | "double" | "double precision" => "double",
+        "float4" | "real" => "real",
+        "character varying" | "varchar" => "varchar",
+        "character" | "char" | "bpchar" => "char",
+        "decimal" | "numeric" => "numeric",
+        "timestamp with time zone" | "timestamptz" => "timestamptz",
+        "timestamp without time zone" | "timestamp" => "timestamp",
+        "time without time zone" | "time" => "time",
+        v => v,
+    };
+    //the display width of the mysql integers, `int(11)`
+    let args = match name {
+        "bigint" | "integer" | "smallint" | "tinyint" => String::new(),
+        _ => args,
+    };
+    (name.to_string(), args)
 }
 
 /// Mapper Column and ColumnType
@@ -0,0 +1,90 @@
+use crate::decode::value_type;
+use crate::table_sync::ColumnMapper;
+use rbs::Value;
+use std::collections::HashMap;
+
+/// wrap a mapper with the type overrides, the precedence is:
+/// 1. the column override
+/// 2. the non empty string value, it's the type hint of the column
+/// 3. the `Value::Ext` override by the ext type, for example `Decimal`,`Uuid`,`DateTime`
+/// 4. the value override by the variant name, for example `Bool`,`String`,`I64`
+/// 5. the wrapped mapper
+///
+/// the ext and the value overrides only replace the generic type of the wrapped mapper, the type
+/// it picks by the column name is kept, for example `id`/`*_id` => `VARCHAR(50)` of the empty string
+/// ```rust
+/// use rbatis::table_sync::{MysqlTableMapper, OverrideMapper};
+///
+/// let mapper = OverrideMapper::new(MysqlTableMapper {})
+///     .ext("Decimal", "DECIMAL(18,4)")
+///     .ext("Uuid", "BINARY(16)")
+///     .value("Bool", "BOOLEAN")
+///     .value("String", "VARCHAR(255)")
+///     .column("remark", "TEXT");
+/// ```
+pub struct OverrideMapper<M: ColumnMapper> {
+    pub mapper: M,
+    /// column name => column type
+    pub columns: HashMap<String, String>,
+    /// ext type => column type
+    pub ext_types: HashMap<String, String>,
+    /// value variant name => column type
+    pub value_types: HashMap<String, String>,
+}
+
+impl<M: ColumnMapper> OverrideMapper<M> {
+    pub fn new(mapper: M) -> Self {
+        Self {
+            mapper,
+            columns: HashMap::new(),
+            ext_types: HashMap::new(),
+            value_types: HashMap::new(),
+        }
+    }
+
+    /// the type of a column, for example `column("price", "DECIMAL(18,4)")`
+    pub fn column(mut self, column: &str, column_type: &str) -> Self {
+        self.columns
+            .insert(column.to_string(), column_type.to_string());
+        self
+    }
+
+    /// the type of a `Value::Ext`, for example `ext("Uuid", "BINARY(16)")`
+    pub fn ext(mut self, ext_type: &str, column_type: &str) -> Self {
+        self.ext_types
+            .insert(ext_type.to_string(), column_type.to_string());
+        self
+    }
+
+    /// the type of a `Value` variant, for example `value("Bool", "BOOLEAN")`.
+    /// the `String` override is only used by the empty string
+    pub fn value(mut self, variant: &str, column_type: &str) -> Self {
+        self.value_types
+            .insert(variant.to_string(), column_type.to_string());
+        self
+    }
+}
+
+impl<M: ColumnMapper> ColumnMapper for OverrideMapper<M> {
+    fn driver_type(&self) -> String {
+        self.mapper.driver_type()
+    }
+
+    fn get_column_type(&self, column: &str, v: &Value) -> String {
+        if let Some(column_type) = self.columns.get(column) {
+            return column_type.clone();
+        }
+        let column_type = match v {
+            Value::String(s) if !s.is_empty() => return s.to_string(),
+            Value::Ext(t, _) => self.ext_types.get(*t),
+            _ => self.value_types.get(&value_type(v)),
+        };
+        let mapped = self.mapper.get_column_type(column, v);
+        match column_type {
+            Some(column_type) if mapped == self.mapper.get_column_type("", v) => {
+                column_type.clone()
+            }
+            _ => mapped,
+        }
+    }
+}
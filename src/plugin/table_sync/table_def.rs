@@ -0,0 +1,445 @@
+use crate::crud_traits::Table;
+use crate::executor::Executor;
+use crate::schema::{self, TableInfo};
+use crate::table_sync::{is_same_type, ColumnMapper, ColumnMismatch, SyncReport, PRIMARY_KEY};
+use crate::Error;
+use futures_core::future::BoxFuture;
+use log::warn;
+use rbs::value::map::ValueMap;
+use rbs::Value;
+
+/// the constraints and indexes of a table for [`sync`](crate::table_sync::sync),
+/// the column types are still mapped by the [`ColumnMapper`].
+///
+/// an existing table only adds the missing columns, indexes and foreign keys(by the columns).
+/// the primary key, the `NOT NULL` of the existing columns and the foreign keys of sqlite can't be altered,
+/// they are reported into `warnings`. the `NOT NULL` is compared in both ways: a nullable column declared `NOT NULL`
+/// and a `NOT NULL` column(not of the primary key) not declared, by [`TableDef::not_null`] or the column type
+/// ```rust
+/// use rbatis::executor::Executor;
+/// use rbatis::table_sync::{ForeignKeyDef, SqliteTableMapper, TableDef};
+///
+/// pub async fn sync_post(conn: &dyn Executor) -> Result<(), rbatis::Error> {
+///     let table = rbs::value!{"id": "INTEGER", "version": "INTEGER", "author_id": "INTEGER", "title": "TEXT", "status": "INTEGER"};
+///     let report = TableDef::new("post")
+///         .primary_key(&["id", "version"])
+///         .not_null("title")
+///         .default_value("status", "0")
+///         .index("idx_post_title", &["title"])
+///         .unique("uk_post_author_title", &["author_id", "title"])
+///         .foreign_key(ForeignKeyDef::new("fk_post_author", &["author_id"], "author", &["id"]).on_delete("CASCADE"))
+///         .sync(conn, &SqliteTableMapper{}, table)
+///         .await?;
+///     println!("{:?}", report.ddl);
+///     Ok(())
+/// }
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TableDef {
+    pub name: String,
+    /// empty is the legacy primary key: the column `id` of an empty type
+    pub primary_key: Vec<String>,
+    pub columns: Vec<ColumnDef>,
+    pub indexes: Vec<IndexDef>,
+    pub foreign_keys: Vec<ForeignKeyDef>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnDef {
+    pub name: String,
+    pub not_null: bool,
+    /// the default value expression, for example `0`, `'none'`, `CURRENT_TIMESTAMP`
+    pub default: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IndexDef {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub unique: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ForeignKeyDef {
+    pub name: String,
+    pub columns: Vec<String>,
+    pub ref_table: String,
+    pub ref_columns: Vec<String>,
+    /// for example `CASCADE`, `SET NULL`
+    pub on_delete: Option<String>,
+}
+
+impl ForeignKeyDef {
+    pub fn new(name: &str, columns: &[&str], ref_table: &str, ref_columns: &[&str]) -> Self {
+        Self {
+            name: name.to_string(),
+            columns: to_strings(columns),
+            ref_table: ref_table.to_string(),
+            ref_columns: to_strings(ref_columns),
+            on_delete: None,
+        }
+    }
+
+    pub fn on_delete(mut self, action: &str) -> Self {
+        self.on_delete = Some(action.to_string());
+        self
+    }
+
+    /// `CONSTRAINT name FOREIGN KEY (a) REFERENCES t (b) ON DELETE x`
+    pub fn sql(&self) -> String {
+        let mut sql = format!(
+            "CONSTRAINT {} FOREIGN KEY ({}) REFERENCES {} ({})",
+            self.name,
+            self.columns.join(","),
+            self.ref_table,
+            self.ref_columns.join(",")
+        );
+        if let Some(action) = &self.on_delete {
+            sql.push_str(" ON DELETE ");
+            sql.push_str(action);
+        }
+        sql
+    }
+}
+
+impl IndexDef {
+    /// `CREATE UNIQUE INDEX name ON table (a,b);`
+    pub fn sql(&self, table_name: &str) -> String {
+        format!(
+            "CREATE {}INDEX {} ON {} ({});",
+            if self.unique { "UNIQUE " } else { "" },
+            self.name,
+            table_name,
+            self.columns.join(",")
+        )
+    }
+}
+
+impl TableDef {
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// the table name and the primary key of `#[derive(rbatis::Table)]`
+    pub fn from_table<T: Table>() -> Self {
+        let mut def = Self::new(T::table_name());
+        if let Some(pk) = T::pk_column() {
+            def.primary_key = vec![pk.to_string()];
+        }
+        def
+    }
+
+    /// the def and the column types(as the type hints of the mapper) of an introspected table
+    pub fn from_info(info: &TableInfo) -> (Self, Value) {
+        let mut def = Self::new(&info.name);
+        let mut columns = ValueMap::with_capacity(info.columns.len());
+        for c in &info.columns {
+            columns.insert(
+                Value::String(c.name.clone()),
+                Value::String(c.column_type.clone()),
+            );
+            if !c.nullable && !info.primary_keys.contains(&c.name) {
+                def = def.not_null(&c.name);
+            }
+            if let Some(default) = &c.default {
+                def = def.default_value(&c.name, default);
+            }
+        }
+        def.primary_key = info.primary_keys.clone();
+        for index in &info.indexes {
+            let mut name = index.name.clone();
+            if name.starts_with("sqlite_autoindex_") {
+                //the name of an unique constraint is reserved by sqlite
+                name = format!("uk_{}_{}", info.name, index.columns.join("_"));
+            }
+            def.indexes.push(IndexDef {
+                name,
+                columns: index.columns.clone(),
+                unique: index.unique,
+            });
+        }
+        for fk in &info.foreign_keys {
+            let mut name = fk.name.clone();
+            if name.is_empty() || name.chars().all(|c| c.is_ascii_digit()) {
+                //the foreign key of sqlite is the id
+                name = format!("fk_{}_{}", info.name, fk.columns.join("_"));
+            }
+            def.foreign_keys.push(ForeignKeyDef {
+                name,
+                columns: fk.columns.clone(),
+                ref_table: fk.ref_table.clone(),
+                ref_columns: fk.ref_columns.clone(),
+                on_delete: fk.on_delete.clone(),
+            });
+        }
+        (def, Value::Map(columns))
+    }
+
+    /// a primary key of one or more columns
+    pub fn primary_key(mut self, columns: &[&str]) -> Self {
+        self.primary_key = to_strings(columns);
+        self
+    }
+
+    pub fn not_null(mut self, column: &str) -> Self {
+        self.column_mut(column).not_null = true;
+        self
+    }
+
+    pub fn default_value(mut self, column: &str, default: &str) -> Self {
+        self.column_mut(column).default = Some(default.to_string());
+        self
+    }
+
+    pub fn index(mut self, name: &str, columns: &[&str]) -> Self {
+        self.indexes.push(IndexDef {
+            name: name.to_string(),
+            columns: to_strings(columns),
+            unique: false,
+        });
+        self
+    }
+
+    /// a unique constraint, it's created as an unique index
+    pub fn unique(mut self, name: &str, columns: &[&str]) -> Self {
+        self.indexes.push(IndexDef {
+            name: name.to_string(),
+            columns: to_strings(columns),
+            unique: true,
+        });
+        self
+    }
+
+    pub fn foreign_key(mut self, foreign_key: ForeignKeyDef) -> Self {
+        self.foreign_keys.push(foreign_key);
+        self
+    }
+
+    fn column_mut(&mut self, column: &str) -> &mut ColumnDef {
+        match self.columns.iter().position(|c| c.name == column) {
+            Some(idx) => &mut self.columns[idx],
+            None => {
+                self.columns.push(ColumnDef {
+                    name: column.to_string(),
+                    ..Default::default()
+                });
+                self.columns.last_mut().unwrap()
+            }
+        }
+    }
+
+    /// ` NOT NULL DEFAULT x` of the column
+    fn column_sql(&self, column: &str) -> String {
+        let mut sql = String::new();
+        if let Some(c) = self.columns.iter().find(|c| c.name == column) {
+            if c.not_null {
+                sql.push_str(" NOT NULL");
+            }
+            if let Some(default) = &c.default {
+                sql.push_str(" DEFAULT ");
+                sql.push_str(default);
+            }
+        }
+        sql
+    }
+
+    /// the `CREATE TABLE` with the constraints and the `CREATE INDEX` of the table
+    pub fn create_sql(&self, mapper: &dyn ColumnMapper, table: &ValueMap) -> Vec<String> {
+        let mut sql_columns = vec![];
+        for (k, v) in table {
+            let k = k.as_str().unwrap_or_default();
+            let column_type_value = mapper.get_column_type(k, v);
+            let mut sql_column = format!("{} {}", k, column_type_value);
+            sql_column.push_str(&self.column_sql(k));
+            if self.primary_key.is_empty()
+                && (column_type_value.is_empty() && k.eq("id")
+                    || v.as_str().unwrap_or_default() == "id")
+            {
+                sql_column.push_str(PRIMARY_KEY);
+            }
+            sql_columns.push(sql_column);
+        }
+        if !self.primary_key.is_empty() {
+            sql_columns.push(format!("PRIMARY KEY ({})", self.primary_key.join(",")));
+        }
+        for fk in &self.foreign_keys {
+            sql_columns.push(fk.sql());
+        }
+        let mut ddl = vec![format!(
+            "CREATE TABLE {} ({});",
+            self.name,
+            sql_columns.join(",")
+        )];
+        for index in &self.indexes {
+            ddl.push(index.sql(&self.name));
+        }
+        ddl
+    }
+
+    /// create the table or add the missing columns, indexes and foreign keys
+    pub fn sync<'a>(
+        &'a self,
+        executor: &'a dyn Executor,
+        mapper: &'a dyn ColumnMapper,
+        table: Value,
+    ) -> BoxFuture<'a, Result<SyncReport, Error>> {
+        Box::pin(async move {
+            let report = self.plan(executor, mapper, table).await?;
+            for sql in &report.ddl {
+                executor.exec(sql, vec![]).await?;
+            }
+            if !report.mismatched.is_empty() {
+                warn!(
+                    "table sync '{}' column type mismatched: {:?}",
+                    self.name, report.mismatched
+                );
+            }
+            for warning in &report.warnings {
+                warn!("table sync '{}' {}", self.name, warning);
+            }
+            Ok(report)
+        })
+    }
+
+    /// the dry run of [`TableDef::sync`], only read the existing table and return the ddl
+    pub fn plan<'a>(
+        &'a self,
+        executor: &'a dyn Executor,
+        mapper: &'a dyn ColumnMapper,
+        table: Value,
+    ) -> BoxFuture<'a, Result<SyncReport, Error>> {
+        Box::pin(async move {
+            let m = match table {
+                Value::Map(m) => m,
+                _ => return Err(Error::from("table not is an struct or map!")),
+            };
+            let db_driver_type = executor.driver_type()?;
+            if db_driver_type != mapper.driver_type() {
+                return Err(Error::from(format!(
+                    "table sync mapper driver='{}',db driver='{}'",
+                    mapper.driver_type(),
+                    db_driver_type
+                )));
+            }
+            let name = &self.name;
+            let mut report = SyncReport {
+                table: name.clone(),
+                ..Default::default()
+            };
+            let exists = schema::columns(executor, name).await?;
+            if exists.is_empty() {
+                report.ddl = self.create_sql(mapper, &m);
+                report.added = m
+                    .0
+                    .keys()
+                    .map(|k| k.as_str().unwrap_or_default().to_string())
+                    .collect();
+                report.created = true;
+                return Ok(report);
+            }
+            let primary_key = schema::primary_keys(executor, name).await?;
+            for (k, v) in &m {
+                let k = k.as_str().unwrap_or_default();
+                let column_type = mapper.get_column_type(k, v);
+                match exists.iter().find(|c| c.name.eq_ignore_ascii_case(k)) {
+                    Some(column) => {
+                        let not_null = self.columns.iter().any(|c| c.name == k && c.not_null)
+                            || column_type.to_uppercase().contains("NOT NULL");
+                        if is_same_type(&column_type, &column.column_type) {
+                            report.skipped.push(k.to_string());
+                        } else {
+                            report.mismatched.push(ColumnMismatch {
+                                column: k.to_string(),
+                                expected: column_type,
+                                actual: column.column_type.clone(),
+                            });
+                        }
+                        if not_null && column.nullable {
+                            report
+                                .warnings
+                                .push(format!("column '{}' is nullable, but declared NOT NULL", k));
+                        } else if !not_null
+                            && !column.nullable
+                            && !primary_key.iter().any(|v| v.eq_ignore_ascii_case(k))
+                            && !self.primary_key.iter().any(|v| v.eq_ignore_ascii_case(k))
+                        {
+                            report
+                                .warnings
+                                .push(format!("column '{}' is NOT NULL, but not declared NOT NULL", k));
+                        }
+                    }
+                    None => {
+                        let mut id_key = "";
+                        if self.primary_key.is_empty()
+                            && (k.eq("id") || v.as_str().unwrap_or_default() == "id")
+                        {
+                            id_key = PRIMARY_KEY;
+                        }
+                        report.ddl.push(format!(
+                            "alter table {} add {} {}{} {};",
+                            name,
+                            k,
+                            column_type,
+                            self.column_sql(k),
+                            id_key
+                        ));
+                        report.added.push(k.to_string());
+                    }
+                }
+            }
+            if !self.primary_key.is_empty() && !same_columns(&primary_key, &self.primary_key) {
+                report.warnings.push(format!(
+                    "primary key is ({}), but declared ({})",
+                    primary_key.join(","),
+                    self.primary_key.join(",")
+                ));
+            }
+            if !self.indexes.is_empty() {
+                let indexes = schema::indexes(executor, name).await?;
+                for index in &self.indexes {
+                    let exists = indexes.iter().any(|v| {
+                        v.name.eq_ignore_ascii_case(&index.name)
+                            || (v.unique == index.unique && same_columns(&v.columns, &index.columns))
+                    });
+                    if !exists {
+                        report.ddl.push(index.sql(name));
+                    }
+                }
+            }
+            if !self.foreign_keys.is_empty() {
+                let foreign_keys = schema::foreign_keys(executor, name).await?;
+                for fk in &self.foreign_keys {
+                    let exists = foreign_keys.iter().any(|v| {
+                        v.ref_table.eq_ignore_ascii_case(&fk.ref_table)
+                            && same_columns(&v.columns, &fk.columns)
+                    });
+                    if exists {
+                        continue;
+                    }
+                    if db_driver_type == "sqlite" {
+                        report.warnings.push(format!(
+                            "sqlite can't add the foreign key '{}' to an existing table",
+                            fk.name
+                        ));
+                    } else {
+                        report
+                            .ddl
+                            .push(format!("ALTER TABLE {} ADD {};", name, fk.sql()));
+                    }
+                }
+            }
+            Ok(report)
+        })
+    }
+}
+
+fn to_strings(columns: &[&str]) -> Vec<String> {
+    columns.iter().map(|v| v.to_string()).collect()
+}
+
+fn same_columns(a: &[String], b: &[String]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(a, b)| a.eq_ignore_ascii_case(b))
+}
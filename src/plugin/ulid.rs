@@ -0,0 +1,55 @@
+//! ULID, the unix milliseconds(48 bits) and the randomness(80 bits) in 26 chars of the Crockford's base32.
+//! the randomness of the same millisecond is increased, so the ulids are time-ordered and monotonic in the process.
+use crate::id_generator::IdGenerator;
+use parking_lot::Mutex;
+use rand::{rng, Rng};
+use rbs::Value;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const ALPHABET: &[u8; 32] = b"0123456789ABCDEFGHJKMNPQRSTVWXYZ";
+
+const MAX_RANDOM: u128 = (1 << 80) - 1;
+
+#[derive(Debug, Default)]
+pub struct Ulid {
+    /// the last milliseconds and randomness
+    state: Mutex<(u64, u128)>,
+}
+
+impl Ulid {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the 128 bits of a new ulid
+    pub fn generate_u128(&self) -> u128 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backward")
+            .as_millis() as u64;
+        let mut state = self.state.lock();
+        if now > state.0 {
+            //leave the room of the increment
+            *state = (now, rng().random::<u128>() & (MAX_RANDOM >> 1));
+        } else if state.1 < MAX_RANDOM {
+            state.1 += 1;
+        } else {
+            *state = (state.0 + 1, 0);
+        }
+        ((state.0 as u128) << 80) | state.1
+    }
+
+    /// a new ulid, for example `01ARZ3NDEKTSV4RRFFQ69G5FAV`
+    pub fn generate(&self) -> String {
+        let v = self.generate_u128();
+        (0..26)
+            .map(|idx| ALPHABET[((v >> (5 * (25 - idx))) & 0x1F) as usize] as char)
+            .collect()
+    }
+}
+
+impl IdGenerator for Ulid {
+    fn generate_id(&self) -> Value {
+        Value::String(self.generate())
+    }
+}
@@ -0,0 +1,68 @@
+//! UUID version 7, the unix milliseconds and a counter of the same millisecond, so the uuids are time-ordered
+//! and monotonic in the process.
+use crate::id_generator::IdGenerator;
+use parking_lot::Mutex;
+use rand::{rng, Rng};
+use rbs::Value;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// the 12 bits counter of `rand_a`
+const MAX_COUNTER: u16 = 0xFFF;
+
+#[derive(Debug, Default)]
+pub struct UuidV7 {
+    /// the last milliseconds and counter
+    state: Mutex<(u64, u16)>,
+}
+
+impl UuidV7 {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// the bytes of a new uuid
+    pub fn generate_bytes(&self) -> [u8; 16] {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("Time went backward")
+            .as_millis() as u64;
+        let mut rng = rng();
+        let (millis, counter) = {
+            let mut state = self.state.lock();
+            if now > state.0 {
+                //leave the room of the counter
+                *state = (now, rng.random_range(0..=MAX_COUNTER / 2));
+            } else if state.1 < MAX_COUNTER {
+                state.1 += 1;
+            } else {
+                *state = (state.0 + 1, 0);
+            }
+            *state
+        };
+        let mut bytes: [u8; 16] = rng.random();
+        bytes[..6].copy_from_slice(&millis.to_be_bytes()[2..]);
+        bytes[6] = 0x70 | (counter >> 8) as u8;
+        bytes[7] = counter as u8;
+        bytes[8] = 0x80 | (bytes[8] & 0x3F);
+        bytes
+    }
+
+    /// a new uuid, for example `01890a5d-ac96-774b-bcce-b302099a8057`
+    pub fn generate(&self) -> String {
+        let hex = hex::encode(self.generate_bytes());
+        format!(
+            "{}-{}-{}-{}-{}",
+            &hex[..8],
+            &hex[8..12],
+            &hex[12..16],
+            &hex[16..20],
+            &hex[20..]
+        )
+    }
+}
+
+impl IdGenerator for UuidV7 {
+    fn generate_id(&self) -> Value {
+        Value::String(self.generate())
+    }
+}
@@ -0,0 +1,370 @@
+//! a type-safe select builder, for the filters too conditional for a static py_sql.
+//!
+//! ```rust
+//! use rbatis::query::{col, Order, Query};
+//! #[derive(serde::Serialize, serde::Deserialize, rbatis::Table)]
+//! pub struct Activity {
+//!     pub id: Option<String>,
+//!     pub name: Option<String>,
+//!     pub status: Option<i32>,
+//! }
+//! let name = Some("a");
+//! let mut query = Query::select::<Activity>().filter(col("status").eq(1));
+//! if let Some(name) = name {
+//!     query = query.and(col("name").like(format!("%{}%", name)));
+//! }
+//! let (sql, args) = query.order_by("id", Order::Desc).limit(10).to_sql("mysql");
+//! assert_eq!(sql, "select id,name,status from activity where status = ? and name like ? order by id desc limit 10");
+//! assert_eq!(args, vec![rbs::Value::I32(1), rbs::Value::String("%a%".to_string())]);
+//! ```
+use crate::crud_traits::{limit_sql, Table};
+use crate::executor::Executor;
+use crate::Error;
+use rbs::Value;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::marker::PhantomData;
+
+/// a column of the condition, see [`col`]
+#[derive(Debug, Clone)]
+pub struct Column {
+    pub name: String,
+}
+
+/// create a column of the condition
+pub fn col(name: &str) -> Column {
+    Column {
+        name: name.to_string(),
+    }
+}
+
+impl Column {
+    fn op<V: Serialize>(self, op: &str, v: V) -> Condition {
+        Condition::Column(format!("{} {} ?", self.name, op), vec![rbs::value!(v)])
+    }
+
+    pub fn eq<V: Serialize>(self, v: V) -> Condition {
+        self.op("=", v)
+    }
+
+    pub fn ne<V: Serialize>(self, v: V) -> Condition {
+        self.op("!=", v)
+    }
+
+    pub fn gt<V: Serialize>(self, v: V) -> Condition {
+        self.op(">", v)
+    }
+
+    pub fn ge<V: Serialize>(self, v: V) -> Condition {
+        self.op(">=", v)
+    }
+
+    pub fn lt<V: Serialize>(self, v: V) -> Condition {
+        self.op("<", v)
+    }
+
+    pub fn le<V: Serialize>(self, v: V) -> Condition {
+        self.op("<=", v)
+    }
+
+    pub fn like<V: Serialize>(self, v: V) -> Condition {
+        self.op("like", v)
+    }
+
+    pub fn not_like<V: Serialize>(self, v: V) -> Condition {
+        self.op("not like", v)
+    }
+
+    /// `column in (?,?)`, an empty values is `1 = 0`
+    pub fn is_in<V: Serialize, I: IntoIterator<Item = V>>(self, values: I) -> Condition {
+        self.in_list("in", values)
+    }
+
+    /// `column not in (?,?)`, an empty values is `1 = 1`
+    pub fn not_in<V: Serialize, I: IntoIterator<Item = V>>(self, values: I) -> Condition {
+        self.in_list("not in", values)
+    }
+
+    fn in_list<V: Serialize, I: IntoIterator<Item = V>>(self, op: &str, values: I) -> Condition {
+        let args: Vec<Value> = values.into_iter().map(|v| rbs::value!(v)).collect();
+        if args.is_empty() {
+            //`in ()` is a syntax error
+            let sql = if op == "in" { "1 = 0" } else { "1 = 1" };
+            return Condition::Column(sql.to_string(), vec![]);
+        }
+        let placeholders = vec!["?"; args.len()].join(",");
+        Condition::Column(format!("{} {} ({})", self.name, op, placeholders), args)
+    }
+
+    pub fn between<V: Serialize>(self, start: V, end: V) -> Condition {
+        Condition::Column(
+            format!("{} between ? and ?", self.name),
+            vec![rbs::value!(start), rbs::value!(end)],
+        )
+    }
+
+    pub fn is_null(self) -> Condition {
+        Condition::Column(format!("{} is null", self.name), vec![])
+    }
+
+    pub fn is_not_null(self) -> Condition {
+        Condition::Column(format!("{} is not null", self.name), vec![])
+    }
+}
+
+/// a where condition, combine with [`Condition::and`] and [`Condition::or`]
+#[derive(Debug, Clone)]
+pub enum Condition {
+    /// a sql with `?` placeholders and the args, it's wrapped by `()` in an and/or group
+    Raw(String, Vec<Value>),
+    /// a predicate of one column built by [`col`], for example `name like ?`
+    Column(String, Vec<Value>),
+    And(Vec<Condition>),
+    Or(Vec<Condition>),
+}
+
+impl Condition {
+    pub fn and(self, other: Condition) -> Condition {
+        match self {
+            Condition::And(mut items) => {
+                items.push(other);
+                Condition::And(items)
+            }
+            v => Condition::And(vec![v, other]),
+        }
+    }
+
+    pub fn or(self, other: Condition) -> Condition {
+        match self {
+            Condition::Or(mut items) => {
+                items.push(other);
+                Condition::Or(items)
+            }
+            v => Condition::Or(vec![v, other]),
+        }
+    }
+
+    /// write the sql into `sql` and the args into `args`,
+    /// `nested` means the and/or group and the raw sql need to be wrapped by `()`
+    fn write(&self, nested: bool, sql: &mut String, args: &mut Vec<Value>) {
+        let (items, sep) = match self {
+            Condition::Raw(raw, values) => {
+                if nested {
+                    sql.push('(');
+                    sql.push_str(raw);
+                    sql.push(')');
+                } else {
+                    sql.push_str(raw);
+                }
+                args.extend(values.iter().cloned());
+                return;
+            }
+            Condition::Column(column, values) => {
+                sql.push_str(column);
+                args.extend(values.iter().cloned());
+                return;
+            }
+            Condition::And(items) => (items, " and "),
+            Condition::Or(items) => (items, " or "),
+        };
+        let wrap = nested && items.len() > 1;
+        if wrap {
+            sql.push('(');
+        }
+        for (idx, item) in items.iter().enumerate() {
+            if idx != 0 {
+                sql.push_str(sep);
+            }
+            item.write(true, sql, args);
+        }
+        if wrap {
+            sql.push(')');
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Order {
+    Asc,
+    Desc,
+}
+
+/// the entry of the builders
+pub struct Query;
+
+impl Query {
+    /// select the columns and table of `#[derive(rbatis::Table)]`
+    pub fn select<T: Table + DeserializeOwned>() -> Select<T> {
+        let columns = T::table_columns()
+            .iter()
+            .map(|c| {
+                if c.column == c.field {
+                    c.column.to_string()
+                } else {
+                    format!("{} as {}", c.column, c.field)
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(",");
+        let mut select = Query::select_from::<T>(T::table_name());
+        if !columns.is_empty() {
+            select.columns = columns;
+        }
+        select
+    }
+
+    /// select `*` from `table_name` and decode into `T`
+    pub fn select_from<T: DeserializeOwned>(table_name: &str) -> Select<T> {
+        Select {
+            table_name: table_name.to_string(),
+            columns: "*".to_string(),
+            condition: None,
+            order_by: vec![],
+            limit: None,
+            offset: None,
+            phantom: PhantomData,
+        }
+    }
+}
+
+/// the select builder, see [`Query::select`]
+#[derive(Debug)]
+pub struct Select<T> {
+    table_name: String,
+    columns: String,
+    condition: Option<Condition>,
+    order_by: Vec<(String, Order)>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+    phantom: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> Select<T> {
+    /// select only `columns`
+    pub fn columns(mut self, columns: &[&str]) -> Self {
+        if !columns.is_empty() {
+            self.columns = columns.join(",");
+        }
+        self
+    }
+
+    /// same as [`Select::and`]
+    pub fn filter(self, condition: Condition) -> Self {
+        self.and(condition)
+    }
+
+    /// `where (...) and condition`
+    pub fn and(mut self, condition: Condition) -> Self {
+        self.condition = Some(match self.condition.take() {
+            None => condition,
+            Some(v) => v.and(condition),
+        });
+        self
+    }
+
+    /// `where (...) or condition`
+    pub fn or(mut self, condition: Condition) -> Self {
+        self.condition = Some(match self.condition.take() {
+            None => condition,
+            Some(v) => v.or(condition),
+        });
+        self
+    }
+
+    pub fn order_by(mut self, column: &str, order: Order) -> Self {
+        self.order_by.push((column.to_string(), order));
+        self
+    }
+
+    pub fn limit(mut self, limit: u64) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    pub fn offset(mut self, offset: u64) -> Self {
+        self.offset = Some(offset);
+        self
+    }
+
+    fn where_sql(&self, sql: &mut String, args: &mut Vec<Value>) {
+        if let Some(condition) = &self.condition {
+            sql.push_str(" where ");
+            condition.write(false, sql, args);
+        }
+    }
+
+    /// build the sql with `?` placeholders(the driver will replace them) and the args.
+    /// the limit/offset are written as the dialect of `driver_type`
+    pub fn to_sql(&self, driver_type: &str) -> (String, Vec<Value>) {
+        let mut args = vec![];
+        let mut sql = format!("select {} from {}", self.columns, self.table_name);
+        self.where_sql(&mut sql, &mut args);
+        if !self.order_by.is_empty() {
+            sql.push_str(" order by ");
+            for (idx, (column, order)) in self.order_by.iter().enumerate() {
+                if idx != 0 {
+                    sql.push(',');
+                }
+                sql.push_str(column);
+                if *order == Order::Desc {
+                    sql.push_str(" desc");
+                }
+            }
+        }
+        sql.push_str(&limit_sql(
+            !self.order_by.is_empty(),
+            self.limit,
+            self.offset,
+            driver_type,
+        ));
+        (sql, args)
+    }
+
+    /// same as [`Select::to_sql`], but the placeholders are the dialect of `driver_type`,
+    /// for example `$1` of postgres and `@P1` of mssql
+    pub fn to_dialect_sql(&self, driver_type: &str) -> (String, Vec<Value>) {
+        let (sql, args) = self.to_sql(driver_type);
+        let sql = match driver_type {
+            "postgres" => rbdc::impl_exchange("$", 1, &sql),
+            "mssql" => rbdc::impl_exchange("@P", 1, &sql),
+            _ => sql,
+        };
+        (sql, args)
+    }
+
+    pub async fn fetch_all(&self, executor: &dyn Executor) -> Result<Vec<T>, Error> {
+        let (sql, args) = self.to_sql(executor.driver_type()?);
+        let values = executor.query(&sql, args).await?;
+        crate::decode_naming(values, executor.rb_ref().naming_strategy)
+    }
+
+    /// fetch the first row, the limit of the builder is replaced by 1
+    pub async fn fetch_one(&self, executor: &dyn Executor) -> Result<Option<T>, Error> {
+        let mut select = self.clone();
+        select.limit = Some(1);
+        Ok(select.fetch_all(executor).await?.into_iter().next())
+    }
+
+    /// `select count(1) as count from table where ...`, the order_by/limit/offset are ignored
+    pub async fn count(&self, executor: &dyn Executor) -> Result<u64, Error> {
+        let mut args = vec![];
+        let mut sql = format!("select count(1) as count from {}", self.table_name);
+        self.where_sql(&mut sql, &mut args);
+        let values = executor.query(&sql, args).await?;
+        crate::decode(values)
+    }
+}
+
+impl<T> Clone for Select<T> {
+    fn clone(&self) -> Self {
+        Select {
+            table_name: self.table_name.clone(),
+            columns: self.columns.clone(),
+            condition: self.condition.clone(),
+            order_by: self.order_by.clone(),
+            limit: self.limit,
+            offset: self.offset,
+            phantom: PhantomData,
+        }
+    }
+}
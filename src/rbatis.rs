@@ -1,11 +1,14 @@
+use crate::crud_traits::Table;
+use crate::decode::NamingStrategy;
 use crate::executor::{Executor, RBatisConnExecutor, RBatisTxExecutor};
+use crate::id_generator::{IdGenerator, TableIdGenerator};
 use crate::intercept_log::LogInterceptor;
 use crate::plugin::intercept::Intercept;
 use crate::plugin::intercept_page::PageIntercept;
 use crate::snowflake::Snowflake;
-use crate::table_sync::{sync, ColumnMapper};
+use crate::table_sync::{plan, sync, ColumnMapper, SyncReport};
 use crate::{DefaultPool, Error};
-use dark_std::sync::SyncVec;
+use dark_std::sync::{SyncHashMap, SyncVec};
 use log::LevelFilter;
 use rbdc::pool::ConnectionManager;
 use rbdc::pool::Pool;
@@ -23,8 +26,13 @@ pub struct RBatis {
     pub pool: Arc<OnceLock<Box<dyn Pool>>>,
     // intercept vec(default the intercepts[0] is a log interceptor)
     pub intercepts: Arc<SyncVec<Arc<dyn Intercept>>>,
-    //rb task id gen
-    pub task_id_generator: Arc<Snowflake>,
+    //rb task id gen, it's `Arc<Snowflake>` before the `IdGenerator`(assign an `Arc<Snowflake>` still works),
+    //the `task_id_generator.generate()` is deprecated, use `generate_i64()`
+    pub task_id_generator: Arc<dyn IdGenerator>,
+    // table name => the generator of the empty primary key of the crud insert
+    pub id_generators: Arc<SyncHashMap<String, TableIdGenerator>>,
+    // column name <=> field name of the decode and the crud macros
+    pub naming_strategy: NamingStrategy,
 }
 
 impl Default for RBatis {
@@ -33,6 +41,8 @@ impl Default for RBatis {
             pool: Arc::new(Default::default()),
             intercepts: Arc::new(SyncVec::new()),
             task_id_generator: Arc::new(Snowflake::default()),
+            id_generators: Arc::new(SyncHashMap::new()),
+            naming_strategy: NamingStrategy::None,
         }
     }
 }
@@ -74,10 +84,10 @@ impl RBatis {
         }
         let mut option = driver.default_option();
         option.set_uri(url)?;
-        let pool = DefaultPool::new(ConnectionManager::new_arc(
-            Arc::new(Box::new(driver)),
-            Arc::new(option),
-        ))?;
+        let pool = DefaultPool::new(ConnectionManager {
+            driver: Arc::new(Box::new(driver)),
+            option: Arc::new(option),
+        })?;
         self.pool
             .set(Box::new(pool))
             .map_err(|_e| Error::from("pool set fail!"))?;
@@ -104,10 +114,7 @@ impl RBatis {
         driver: Driver,
         option: ConnectOptions,
     ) -> Result<(), Error> {
-        let pool = Pool::new(ConnectionManager::new_arc(
-            Arc::new(Box::new(driver)),
-            Arc::new(Box::new(option)),
-        ))?;
+        let pool = Pool::new(ConnectionManager::new_options(driver, option))?;
         self.pool
             .set(Box::new(pool))
             .map_err(|_e| Error::from("pool set fail!"))?;
@@ -171,7 +178,7 @@ impl RBatis {
         let pool = self.get_pool()?;
         let conn = pool.get().await?;
         Ok(RBatisConnExecutor::new(
-            self.task_id_generator.generate(),
+            self.task_id_generator.generate_i64(),
             conn,
             self.clone(),
         ))
@@ -187,7 +194,7 @@ impl RBatis {
         let pool = self.get_pool()?;
         let conn = pool.get_timeout(d).await?;
         Ok(RBatisConnExecutor::new(
-            self.task_id_generator.generate(),
+            self.task_id_generator.generate_i64(),
             conn,
             self.clone(),
         ))
@@ -287,7 +294,56 @@ impl RBatis {
         None
     }
 
-    /// create table if not exists, add column if not exists
+    /// the crud insert assign the empty(`null` or `""`) `column` of `table_name` by `generator`,
+    /// the `last_insert_id` of the result is an array of the `column` of every inserted row
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use rbatis::RBatis;
+    /// use rbatis::ulid::Ulid;
+    ///
+    /// let rb = RBatis::new();
+    /// rb.set_id_generator("activity", "id", Arc::new(Ulid::new()));
+    /// ```
+    pub fn set_id_generator(&self, table_name: &str, column: &str, generator: Arc<dyn IdGenerator>) {
+        self.id_generators.insert(
+            table_name.to_string(),
+            TableIdGenerator {
+                column: column.to_string(),
+                generator,
+            },
+        );
+    }
+
+    /// [`set_id_generator`](Self::set_id_generator) of the table name and the `#[column(pk)]`(or `id`) of `#[derive(rbatis::Table)]`
+    /// ```rust
+    /// use std::sync::Arc;
+    /// use rbatis::RBatis;
+    /// use rbatis::uuid_v7::UuidV7;
+    ///
+    /// #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Table)]
+    /// pub struct Activity {
+    ///     #[column(pk)]
+    ///     pub code: Option<String>,
+    ///     pub name: Option<String>,
+    /// }
+    ///
+    /// let rb = RBatis::new();
+    /// rb.set_table_id_generator::<Activity>(Arc::new(UuidV7::new()));
+    /// assert_eq!(rb.get_id_generator("activity").unwrap().column, "code");
+    /// ```
+    pub fn set_table_id_generator<T: Table>(&self, generator: Arc<dyn IdGenerator>) {
+        self.set_id_generator(T::table_name(), T::pk_column().unwrap_or("id"), generator);
+    }
+
+    pub fn get_id_generator(&self, table_name: &str) -> Option<&TableIdGenerator> {
+        self.id_generators.get(table_name)
+    }
+
+    pub fn remove_id_generator(&self, table_name: &str) -> Option<TableIdGenerator> {
+        self.id_generators.remove(&table_name.to_string())
+    }
+
+    /// create table if not exists, add column if not exists, see [`sync`](crate::table_sync::sync)
     ///
     /// ```rust
     /// use rbatis::executor::Executor;
@@ -350,7 +406,18 @@ impl RBatis {
         column_mapper: &dyn ColumnMapper,
         table: &T,
         table_name: &str,
-    ) -> Result<(), Error> {
+    ) -> Result<SyncReport, Error> {
         sync(executor, column_mapper, to_value!(table), table_name).await
     }
+
+    /// the dry run of [`RBatis::sync`], return the report and the ddl without executing them,
+    /// see [`plan`](crate::table_sync::plan)
+    pub async fn sync_plan<T: Serialize>(
+        executor: &dyn Executor,
+        column_mapper: &dyn ColumnMapper,
+        table: &T,
+        table_name: &str,
+    ) -> Result<SyncReport, Error> {
+        plan(executor, column_mapper, rbs::value!(table), table_name).await
+    }
 }
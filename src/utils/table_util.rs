@@ -83,7 +83,7 @@ macro_rules! table_field_set {
         let vec = &$vec_ref;
         let mut ids = std::collections::HashSet::with_capacity(vec.len());
         for item in vec {
-             match &item $(.$field_name)+ {
+             match $crate::utils::table_util::LoadKey::load_key(&item $(.$field_name)+) {
                 std::option::Option::Some(v) => {
                     ids.insert(v);
                 }
@@ -96,7 +96,7 @@ macro_rules! table_field_set {
         let vec = $vec_ref;
         let mut ids = std::collections::HashSet::with_capacity(vec.len());
         for item in vec {
-             match item $(.$field_name)+.to_owned() {
+             match $crate::utils::table_util::LoadKey::load_key(&item $(.$field_name)+).cloned() {
                 std::option::Option::Some(v) => {
                     ids.insert(v);
                 }
@@ -128,8 +128,8 @@ macro_rules! table_field_map {
         let vec = $vec_ref;
         let mut ids = std::collections::HashMap::with_capacity(vec.len());
         for item in vec {
-              match item $(.$field_name)+ {
-                std::option::Option::Some(ref v) => {
+              match $crate::utils::table_util::LoadKey::load_key(&item $(.$field_name)+) {
+                std::option::Option::Some(v) => {
                     ids.insert(v.clone(), item);
                 }
                 _ => {}
@@ -139,6 +139,170 @@ macro_rules! table_field_map {
     }};
 }
 
+/// the key field of [`table_field_set!`]/[`table_field_map!`] and [`table_load_many!`]/[`table_load_one!`],
+/// an `Option` field or a not null key field
+pub trait LoadKey {
+    type Key: std::hash::Hash + Eq + Clone;
+
+    /// `None` is not loaded
+    fn load_key(&self) -> Option<&Self::Key>;
+}
+
+impl<T: std::hash::Hash + Eq + Clone> LoadKey for Option<T> {
+    type Key = T;
+
+    fn load_key(&self) -> Option<&T> {
+        self.as_ref()
+    }
+}
+
+macro_rules! impl_load_key {
+    ($($t:ty)*) => {
+        $(
+            impl LoadKey for $t {
+                type Key = $t;
+
+                fn load_key(&self) -> Option<&$t> {
+                    Some(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_load_key!(String i8 i16 i32 i64 u8 u16 u32 u64 bool);
+
+/// the max keys of a `select_in_column` of [`table_load_many!`]/[`table_load_one!`], the more keys are selected
+/// by the chunks, under the parameter limits of the databases(mssql 2100, sqlite 999 before 3.32)
+pub const LOAD_CHUNK_SIZE: usize = 500;
+
+/// one-to-many batch load, avoid the N+1 queries.
+/// select the children `where column in (parents.parent_field)` by `select_in_column`(gen by `crud!`/`impl_select!`,
+/// one query of every [`LOAD_CHUNK_SIZE`] keys), return an async `Result<HashMap<key,Vec<Child>>>` grouped by the child field,
+/// or write the children into `parent.children_field` if give `=> children_field`(the child must be `Clone`).
+///
+/// `column` is the table column of the child field, a `&str` or the column constant of `derive(Table)`.
+/// the key fields are `Option` or the not null keys(`String`, the integers), see [`LoadKey`].
+///
+/// for example:
+/// ```rust
+///use std::collections::HashMap;
+///use rbatis::{table_load_many, Error, RBatis};
+///#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+///pub struct Post{
+///   pub id: Option<String>,
+///   #[serde(skip)]
+///   pub comments: Vec<Comment>,
+///}
+///#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+///pub struct Comment{
+///   pub id: Option<String>,
+///   #[serde(rename = "postId")]
+///   pub post_id: Option<String>,
+///}
+///rbatis::crud!(Comment{});
+///
+///async fn load(rb: &RBatis, mut posts: Vec<Post>) -> Result<(), Error> {
+///   //select * from comment where postId in (?,?...)
+///   let comments: HashMap<String, Vec<Comment>> = table_load_many!(rb, &posts, id, Comment, post_id, "postId").await?;
+///   //or write into post.comments
+///   table_load_many!(rb, &mut posts, id, Comment, post_id, "postId" => comments).await?;
+///   Ok(())
+///}
+///```
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! table_load_many {
+    ($executor:expr, $parents:expr, $parent_field:ident, $child:ty, $child_field:ident, $column:expr) => {
+        async {
+            let parents = $parents;
+            let keys: std::vec::Vec<_> = $crate::table_field_set!(&*parents, $parent_field).into_iter().cloned().collect();
+            let mut groups = std::collections::HashMap::with_capacity(keys.len());
+            for keys in keys.chunks($crate::utils::table_util::LOAD_CHUNK_SIZE) {
+                for child in <$child>::select_in_column($executor, $column, keys).await? {
+                    if let std::option::Option::Some(key) = $crate::utils::table_util::LoadKey::load_key(&child.$child_field).cloned() {
+                        groups.entry(key).or_insert_with(std::vec::Vec::new).push(child);
+                    }
+                }
+            }
+            std::result::Result::Ok::<_, $crate::Error>(groups)
+        }
+    };
+    ($executor:expr, $parents:expr, $parent_field:ident, $child:ty, $child_field:ident, $column:expr => $children_field:ident) => {
+        async {
+            let parents = $parents;
+            let groups = $crate::table_load_many!($executor, &*parents, $parent_field, $child, $child_field, $column).await?;
+            for parent in parents.iter_mut() {
+                if let std::option::Option::Some(key) = $crate::utils::table_util::LoadKey::load_key(&parent.$parent_field) {
+                    //the parents of the same key take the same children
+                    parent.$children_field = groups.get(key).cloned().unwrap_or_default();
+                }
+            }
+            std::result::Result::Ok::<(), $crate::Error>(())
+        }
+    };
+}
+
+/// many-to-one batch load, avoid the N+1 queries.
+/// select the parents `where column in (children.child_field)` by `select_in_column`(gen by `crud!`/`impl_select!`,
+/// one query of every [`LOAD_CHUNK_SIZE`] keys), return an async `Result<HashMap<key,Parent>>` by [`table_field_map!`],
+/// or write the parent into `child.parent_field: Option<Parent>` if give `=> parent_field`(the parent must be `Clone`).
+///
+/// `column` is the table column of the parent field, a `&str` or the column constant of `derive(Table)`.
+/// the key fields are `Option` or the not null keys(`String`, the integers), see [`LoadKey`].
+///
+/// for example:
+/// ```rust
+///use std::collections::HashMap;
+///use rbatis::{table_load_one, Error, RBatis};
+///#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Table)]
+///pub struct Post{
+///   pub id: Option<String>,
+///}
+///#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+///pub struct Comment{
+///   pub id: Option<String>,
+///   pub post_id: String,
+///   #[serde(skip)]
+///   pub post: Option<Post>,
+///}
+///
+///async fn load(rb: &RBatis, mut comments: Vec<Comment>) -> Result<(), Error> {
+///   //select * from post where id in (?,?...)
+///   let posts: HashMap<String, Post> = table_load_one!(rb, &comments, post_id, Post, id, PostColumns::ID).await?;
+///   //or write into comment.post
+///   table_load_one!(rb, &mut comments, post_id, Post, id, PostColumns::ID => post).await?;
+///   Ok(())
+///}
+///```
+#[allow(unused_macros)]
+#[macro_export]
+macro_rules! table_load_one {
+    ($executor:expr, $children:expr, $child_field:ident, $parent:ty, $parent_field:ident, $column:expr) => {
+        async {
+            let children = $children;
+            let keys: std::vec::Vec<_> = $crate::table_field_set!(&*children, $child_field).into_iter().cloned().collect();
+            let mut parents = std::vec::Vec::with_capacity(keys.len());
+            for keys in keys.chunks($crate::utils::table_util::LOAD_CHUNK_SIZE) {
+                parents.extend(<$parent>::select_in_column($executor, $column, keys).await?);
+            }
+            std::result::Result::Ok::<_, $crate::Error>($crate::table_field_map!(parents, $parent_field))
+        }
+    };
+    ($executor:expr, $children:expr, $child_field:ident, $parent:ty, $parent_field:ident, $column:expr => $field:ident) => {
+        async {
+            let children = $children;
+            let parents = $crate::table_load_one!($executor, &*children, $child_field, $parent, $parent_field, $column).await?;
+            for child in children.iter_mut() {
+                if let std::option::Option::Some(key) = $crate::utils::table_util::LoadKey::load_key(&child.$child_field) {
+                    child.$field = parents.get(key).cloned();
+                }
+            }
+            std::result::Result::Ok::<(), $crate::Error>(())
+        }
+    };
+}
+
 /// Gets the BtreeMap collection of member attributes of the target Vec
 /// for example:
 /// ```rust
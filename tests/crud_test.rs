@@ -83,6 +83,30 @@ mod test {
         }
     }
 
+    #[derive(Debug, Clone)]
+    struct MockPgDriver {}
+
+    impl Driver for MockPgDriver {
+        fn name(&self) -> &str {
+            "postgres"
+        }
+
+        fn connect(&self, _url: &str) -> BoxFuture<Result<Box<dyn Connection>, Error>> {
+            Box::pin(async { Ok(Box::new(MockConnection {}) as Box<dyn Connection>) })
+        }
+
+        fn connect_opt<'a>(
+            &'a self,
+            _option: &'a dyn ConnectOptions,
+        ) -> BoxFuture<'a, Result<Box<dyn Connection>, Error>> {
+            Box::pin(async { Ok(Box::new(MockConnection {}) as Box<dyn Connection>) })
+        }
+
+        fn default_option(&self) -> Box<dyn ConnectOptions> {
+            Box::new(MockConnectOptions {})
+        }
+    }
+
     #[derive(Clone, Debug)]
     struct MockRowMetaData {
         sql: String,
@@ -90,7 +114,7 @@ mod test {
 
     impl MetaData for MockRowMetaData {
         fn column_len(&self) -> usize {
-            if self.sql.contains("select count") {
+            if self.sql.contains("select count") || self.sql.contains(" as value ") {
                 1
             } else {
                 2
@@ -100,6 +124,8 @@ mod test {
         fn column_name(&self, i: usize) -> String {
             if self.sql.contains("select count") {
                 "count".to_string()
+            } else if self.sql.contains(" as value ") {
+                "value".to_string()
             } else {
                 if i == 0 {
                     "sql".to_string()
@@ -128,7 +154,7 @@ mod test {
         }
 
         fn get(&mut self, i: usize) -> Result<Value, Error> {
-            if self.sql.contains("select count") {
+            if self.sql.contains("select count") || self.sql.contains(" as value ") {
                 Ok(Value::U64(self.count))
             } else {
                 if i == 0 {
@@ -144,23 +170,30 @@ mod test {
     struct MockConnection {}
 
     impl Connection for MockConnection {
-        fn get_rows(
+        fn exec_rows(
             &mut self,
             sql: &str,
             params: Vec<Value>,
-        ) -> BoxFuture<Result<Vec<Box<dyn Row>>, Error>> {
+        ) -> BoxFuture<'_, Result<futures::stream::BoxStream<'_, Result<Box<dyn Row>, Error>>, Error>> {
             let sql = sql.to_string();
             Box::pin(async move {
                 let data = Box::new(MockRow { sql: sql, count: 1 }) as Box<dyn Row>;
-                Ok(vec![data])
+                let s: futures::stream::BoxStream<'_, Result<Box<dyn Row>, Error>> = Box::pin(futures::stream::iter(vec![Ok(data)]));
+                Ok(s)
             })
         }
 
         fn exec(&mut self, sql: &str, params: Vec<Value>) -> BoxFuture<Result<ExecResult, Error>> {
+            //the first auto increment id of an insert
+            let last_insert_id = if sql.starts_with("insert") {
+                Value::U64(6)
+            } else {
+                Value::Null
+            };
             Box::pin(async move {
                 Ok(ExecResult {
                     rows_affected: 0,
-                    last_insert_id: Value::Null,
+                    last_insert_id,
                 })
             })
         }
@@ -205,6 +238,13 @@ mod test {
         pub count: u64, //page count num
     }
 
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+    struct MockAutoTable {
+        pub id: Option<u64>,
+        pub name: Option<String>,
+    }
+    crud!(MockAutoTable {}, "mock_table");
+
     #[test]
     fn test_query_decode() {
         let f = async move {
@@ -315,9 +355,9 @@ mod test {
             let mut rb = RBatis::new();
             let mut opts = MockConnectOptions {};
             opts.set_uri("test");
-            let pool = DefaultPool::new(ConnectionManager::new_opt_box(
-                Box::new(MockDriver {}),
-                Box::new(opts),
+            let pool = DefaultPool::new(ConnectionManager::new_options(
+                MockDriver {},
+                opts,
             ))
             .unwrap();
             rb.init_pool(pool).unwrap();
@@ -440,6 +480,136 @@ mod test {
         block_on(f);
     }
 
+    #[test]
+    fn test_insert_returning() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockPgDriver {}, "test").unwrap();
+            let mut t = MockTable {
+                id: None,
+                name: Some("2".into()),
+                pc_link: None,
+                h5_link: None,
+                pc_banner_img: None,
+                h5_banner_img: None,
+                sort: None,
+                status: Some(2),
+                remark: None,
+                create_time: None,
+                version: None,
+                delete_flag: None,
+                count: 0,
+            };
+            let r = MockTable::insert_returning(&mut rb, &mut t, "count")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            println!("{} [{}]", sql, Value::from(args.clone()));
+            assert_eq!(
+                sql,
+                "insert into mock_table (name,status,count) VALUES (?,?,?) returning *"
+            );
+            //the mock row returned count = 1
+            assert_eq!(t.count, 1);
+            assert_eq!(r.rows_affected, 1);
+            assert_eq!(r.last_insert_id, Value::Array(vec![Value::U64(1)]));
+            //the rows without the key are inserted one by one
+            t.count = 0;
+            let mut tables = vec![t.clone(), t.clone()];
+            let r = MockTable::insert_batch_returning(&mut rb, &mut tables, "id", 10)
+                .await
+                .unwrap();
+            assert_eq!(queue.len(), 2);
+            for _ in 0..2 {
+                let (sql, _) = queue.pop().unwrap();
+                assert_eq!(
+                    sql,
+                    "insert into mock_table (name,status,count) VALUES (?,?,?) returning *"
+                );
+            }
+            assert_eq!(r.rows_affected, 2);
+            assert!(tables.iter().all(|v| v.count == 1));
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_take_row() {
+        let mut rows = vec![
+            rbs::value! {"ID": 2, "name": "b"},
+            rbs::value! {"ID": 1, "name": "a"},
+        ];
+        let row = rbatis::crud_traits::take_row(&mut rows, "id", &Value::U64(1)).unwrap();
+        assert_eq!(row["name"].as_str(), Some("a"));
+        assert_eq!(rows.len(), 1);
+        assert!(rbatis::crud_traits::take_row(&mut rows, "id", &Value::U64(3)).is_none());
+    }
+
+    #[test]
+    fn test_insert_returning_fallback() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let mut tables = vec![
+                MockAutoTable {
+                    id: Some(5),
+                    name: Some("5".into()),
+                },
+                MockAutoTable {
+                    id: None,
+                    name: Some("6".into()),
+                },
+                MockAutoTable {
+                    id: None,
+                    name: Some("7".into()),
+                },
+            ];
+            let r = MockAutoTable::insert_batch_returning(&mut rb, &mut tables, "id", 10)
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            println!("{} [{}]", sql, Value::from(args.clone()));
+            assert_eq!(sql, "insert into mock_table (id,name) VALUES (?,?),(?,?),(?,?)");
+            //the ids of the rows mixed with an explicit id are unknown, they are not written back
+            let ids: Vec<Option<u64>> = tables.iter().map(|v| v.id).collect();
+            assert_eq!(ids, vec![Some(5), None, None]);
+            assert_eq!(
+                r.last_insert_id,
+                Value::Array(vec![Value::U64(5), Value::Null, Value::Null])
+            );
+            //the mock connection return the last_insert_id 6, the next row take 7
+            let mut tables = vec![
+                MockAutoTable {
+                    id: None,
+                    name: Some("6".into()),
+                },
+                MockAutoTable {
+                    id: None,
+                    name: Some("7".into()),
+                },
+            ];
+            let r = MockAutoTable::insert_batch_returning(&mut rb, &mut tables, "id", 10)
+                .await
+                .unwrap();
+            let ids: Vec<Option<u64>> = tables.iter().map(|v| v.id).collect();
+            assert_eq!(ids, vec![Some(6), Some(7)]);
+            assert_eq!(r.last_insert_id, Value::Array(vec![Value::U64(6), Value::U64(7)]));
+            let mut t = MockAutoTable {
+                id: None,
+                name: Some("6".into()),
+            };
+            MockAutoTable::insert_returning(&mut rb, &mut t, "id")
+                .await
+                .unwrap();
+            assert_eq!(t.id, Some(6));
+        };
+        block_on(f);
+    }
+
     #[test]
     fn test_update_by_column() {
         let f = async move {
@@ -558,13 +728,13 @@ mod test {
                 args: &mut Vec<Value>,
                 _result: ResultType<&mut Result<ExecResult, Error>, &mut Result<Vec<Value>, Error>>,
             ) -> Result<Option<bool>, Error> {
-                assert_eq!(sql, "update mock_table set name=?,pc_link=?,h5_link=?,status=?,remark=?,create_time=?,version=?,delete_flag=?,count=? where id = ?");
-                let num = self.num.load(Ordering::Relaxed) + 1;
+                assert_eq!(sql, "update mock_table set name = case id when ? then ? when ? then ? else name end,pc_link = case id when ? then ? when ? then ? else pc_link end,h5_link = case id when ? then ? when ? then ? else h5_link end,status = case id when ? then ? when ? then ? else status end,remark = case id when ? then ? when ? then ? else remark end,create_time = case id when ? then ? when ? then ? else create_time end,version = case id when ? then ? when ? then ? else version end,delete_flag = case id when ? then ? when ? then ? else delete_flag end,count = case id when ? then ? when ? then ? else count end where id in (?,?)");
                 println!("{}", sql);
                 println!("{}", Value::Array(args.clone()));
-                assert_eq!(
-                    args,
-                    &[
+                //batch_size = 2, the first batch is row 1,2 and the second batch is row 3,4
+                let first = self.num.load(Ordering::Relaxed) * 2 + 1;
+                let row = |num: i32| {
+                    vec![
                         Value::String(num.to_string()),
                         Value::String(num.to_string()),
                         Value::String(num.to_string()),
@@ -572,14 +742,24 @@ mod test {
                         Value::String(num.to_string()),
                         Value::Ext(
                             "DateTime",
-                            Box::new(Value::String("2023-10-10T00:00:00+08:00".to_string()))
+                            Box::new(Value::String("2023-10-10T00:00:00+08:00".to_string())),
                         ),
                         Value::I64(num as i64),
                         Value::I32(num),
                         Value::U64(num as u64),
-                        Value::String(num.to_string())
                     ]
-                );
+                };
+                let (a, b) = (row(first), row(first + 1));
+                let mut expect = vec![];
+                for i in 0..a.len() {
+                    expect.push(Value::String(first.to_string()));
+                    expect.push(a[i].clone());
+                    expect.push(Value::String((first + 1).to_string()));
+                    expect.push(b[i].clone());
+                }
+                expect.push(Value::String(first.to_string()));
+                expect.push(Value::String((first + 1).to_string()));
+                assert_eq!(args, &expect);
                 self.num.fetch_add(1, Ordering::Relaxed);
                 return Ok(Some(true));
             }
@@ -1014,6 +1194,129 @@ mod test {
         block_on(f);
     }
 
+    #[test]
+    fn test_select_by_column_cols() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::select_by_column_cols(&mut rb, &["id", "count"], "id", "1")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            println!("{}", sql);
+            assert_eq!(sql, "select id,count from mock_table where id = ?");
+            assert_eq!(args, vec![to_value!("1")]);
+            assert_eq!(r[0].count, 1);
+            assert_eq!(r[0].name, None);
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    struct MockDefaultTable {
+        pub id: Option<String>,
+        #[serde(default)]
+        pub name: String,
+        pub count: u64,
+    }
+    crud!(MockDefaultTable {}, "mock_table", "");
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    struct MockRequiredTable {
+        pub id: Option<String>,
+        pub name: String,
+        pub count: u64,
+    }
+    crud!(MockRequiredTable {}, "mock_table");
+
+    #[test]
+    fn test_select_by_column_cols_not_option() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockDefaultTable::select_by_column_cols(&mut rb, &["id", "count"], "id", "1")
+                .await
+                .unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "select id,count from mock_table where id = ?");
+            assert_eq!(r[0].name, "");
+            assert_eq!(r[0].count, 1);
+            let e = MockRequiredTable::select_by_column_cols(&mut rb, &["id", "count"], "id", "1")
+                .await
+                .unwrap_err();
+            assert!(e.to_string().contains("name"), "{}", e);
+            assert!(e.to_string().contains("#[serde(default)]"), "{}", e);
+            //the empty default columns is `*`
+            MockDefaultTable::select_by_map(&mut rb, to_value! {"id": "1"})
+                .await
+                .unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "select * from mock_table  where id = ?");
+            MockDefaultTable::select_by_column_cols(&mut rb, &[], "id", "1")
+                .await
+                .unwrap();
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "select * from mock_table where id = ?");
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_select_by_column_as() {
+        #[derive(serde::Deserialize)]
+        pub struct CountDTO {
+            pub count: u64,
+        }
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r: Vec<CountDTO> = MockTable::select_by_column_as(&mut rb, &["count"], "id", "1")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            println!("{}", sql);
+            assert_eq!(sql, "select count from mock_table where id = ?");
+            assert_eq!(r[0].count, 1);
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    struct MockColumnTable {
+        pub id: Option<String>,
+        pub name: Option<String>,
+        pub count: u64,
+    }
+    crud!(MockColumnTable{},"mock_column_table","id,count");
+
+    #[test]
+    fn test_select_default_columns() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockColumnTable::select_by_column(&mut rb, "id", "1")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            println!("{}", sql);
+            assert_eq!(sql.trim(), "select id,count from mock_column_table  where id = ?");
+            let r = MockColumnTable::select_by_column_cols(&mut rb, &[], "id", "1")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select id,count from mock_column_table where id = ?");
+        };
+        block_on(f);
+    }
+
     #[test]
     fn test_select_by_table() {
         let f = async move {
@@ -1041,6 +1344,496 @@ mod test {
         block_on(f);
     }
 
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Table)]
+    #[table(name = "mock_derive")]
+    struct MockDeriveTable {
+        #[column(pk, rename = "uid")]
+        pub id: Option<String>,
+        pub name: Option<String>,
+        #[column(insert = false, update = false)]
+        pub create_time: Option<String>,
+        #[column(update = false)]
+        pub version: Option<i64>,
+    }
+
+    #[test]
+    fn test_derive_table() {
+        use rbatis::crud_traits::Table;
+        assert_eq!(MockDeriveTable::table_name(), "mock_derive");
+        assert_eq!(MockDeriveTable::pk_column(), Some("uid"));
+        assert_eq!(MockDeriveTableColumns::ID, "uid");
+        assert_eq!(MockDeriveTable::columns().create_time, "create_time");
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let t = MockDeriveTable {
+                id: Some("1".to_string()),
+                name: Some("a".to_string()),
+                create_time: Some("2024-01-01".to_string()),
+                version: Some(1),
+            };
+            MockDeriveTable::insert(&mut rb, &t).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "insert into mock_derive (uid,name,version) VALUES (?,?,?)");
+            assert_eq!(args, vec![to_value!("1"), to_value!("a"), to_value!(1i64)]);
+
+            MockDeriveTable::update_by_id(&mut rb, &t).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "update mock_derive set name=? where uid = ?");
+            assert_eq!(args, vec![to_value!("a"), to_value!("1")]);
+
+            let _ = MockDeriveTable::select_by_id(&mut rb, "1").await;
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select uid as id,name,create_time,version from mock_derive  where uid = ?"
+            );
+            assert_eq!(args, vec![to_value!("1")]);
+
+            MockDeriveTable::delete_by_column(&mut rb, MockDeriveTableColumns::NAME, "a")
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_derive where name = ?");
+
+            MockDeriveTable::delete_by_id(&mut rb, "1").await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_derive where uid = ?");
+            assert_eq!(args, vec![to_value!("1")]);
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Table)]
+    #[serde(rename_all = "camelCase")]
+    #[table(name = "mock_camel")]
+    struct MockCamelTable {
+        pub id: Option<String>,
+        pub user_name: Option<String>,
+        #[serde(rename = "created")]
+        pub create_time: Option<String>,
+        #[column(rename = "login_count")]
+        pub login_count: Option<i64>,
+        #[serde(skip_serializing)]
+        pub password: Option<String>,
+        #[serde(skip_deserializing)]
+        pub token: Option<String>,
+    }
+
+    #[test]
+    fn test_derive_table_rename_all() {
+        use rbatis::crud_traits::Table;
+        assert_eq!(MockCamelTableColumns::USER_NAME, "userName");
+        assert_eq!(MockCamelTable::columns().create_time, "created");
+        assert_eq!(MockCamelTable::table_columns().len(), 4);
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let t = MockCamelTable {
+                id: Some("1".to_string()),
+                user_name: Some("a".to_string()),
+                create_time: Some("2024-01-01".to_string()),
+                login_count: Some(1),
+                password: Some("p".to_string()),
+                token: None,
+            };
+            MockCamelTable::insert(&mut rb, &t).await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "insert into mock_camel (id,userName,created,login_count) VALUES (?,?,?,?)"
+            );
+            assert_eq!(
+                args,
+                vec![
+                    to_value!("1"),
+                    to_value!("a"),
+                    to_value!("2024-01-01"),
+                    to_value!(1i64)
+                ]
+            );
+
+            let _ = MockCamelTable::select_by_id(&mut rb, "1").await;
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select id,userName,created,login_count as loginCount from mock_camel  where id = ?"
+            );
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_query_builder() {
+        use rbatis::query::{col, Condition, Order, Query};
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let query = Query::select::<MockDeriveTable>()
+                .filter(col("name").like("%a%"))
+                .and(col("version").ge(1).or(col("create_time").is_null()))
+                .and(col("uid").is_in(vec!["1", "2"]))
+                .order_by("version", Order::Desc)
+                .order_by("uid", Order::Asc)
+                .limit(10)
+                .offset(20);
+            let r = query.fetch_all(&rb).await;
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select uid as id,name,create_time,version from mock_derive where name like ? and (version >= ? or create_time is null) and uid in (?,?) order by version desc,uid limit 10 offset 20"
+            );
+            assert_eq!(
+                args,
+                vec![to_value!("%a%"), to_value!(1), to_value!("1"), to_value!("2")]
+            );
+            let count = query.count(&rb).await.unwrap();
+            assert_eq!(count, 1);
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select count(1) as count from mock_derive where name like ? and (version >= ? or create_time is null) and uid in (?,?)"
+            );
+            let (sql, _) = Query::select_from::<MockTable>("mock_table")
+                .filter(col("id").eq("1"))
+                .or(col("id").eq("2"))
+                .limit(1)
+                .to_dialect_sql("mssql");
+            assert_eq!(
+                sql,
+                "select * from mock_table where id = @P1 or id = @P2 order by (select null) offset 0 rows fetch next 1 rows only"
+            );
+            //the raw sql in a group is wrapped
+            let (sql, args) = Query::select_from::<MockTable>("mock_table")
+                .filter(Condition::Raw("a = ? or b = ?".to_string(), vec![to_value!(1), to_value!(2)]))
+                .and(col("id").eq("1"))
+                .to_dialect_sql("mysql");
+            assert_eq!(sql, "select * from mock_table where (a = ? or b = ?) and id = ?");
+            assert_eq!(args, vec![to_value!(1), to_value!(2), to_value!("1")]);
+            let (sql, _) = Query::select_from::<MockTable>("mock_table")
+                .filter(Condition::Raw("a = ? or b = ?".to_string(), vec![to_value!(1), to_value!(2)]))
+                .to_dialect_sql("mysql");
+            assert_eq!(sql, "select * from mock_table where a = ? or b = ?");
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_select_by_map_operator() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::select_by_map(
+                &mut rb,
+                to_value!{
+                    "status":1,
+                    "count >=":2,
+                    "name like":"%a%",
+                    "id in":["1","2"],
+                    "create_time between":[3,4],
+                    "or":[to_value!{"delete_flag is null":()}, to_value!{"delete_flag":0, "version !=":5}],
+                    "order_by":["create_time desc","id"],
+                    "limit":10,
+                    "offset":20,
+                },
+            )
+            .await
+            .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            println!("{}", sql);
+            assert_eq!(
+                sql,
+                "select * from mock_table  where status = ? and count >= ? and name like ? and id in (?,?) and create_time between ? and ? and (delete_flag is null or (delete_flag = ? and version != ?)) order by create_time desc,id limit 10 offset 20"
+            );
+            assert_eq!(
+                args,
+                vec![
+                    to_value!(1),
+                    to_value!(2),
+                    to_value!("%a%"),
+                    to_value!("1"),
+                    to_value!("2"),
+                    to_value!(3),
+                    to_value!(4),
+                    to_value!(0),
+                    to_value!(5)
+                ]
+            );
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_select_by_map_operator_case() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::select_by_map(
+                &mut rb,
+                to_value!{"NAME LIKE":"%a%", "Count >=":2, "名\u{212A}E":3},
+            )
+            .await
+            .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(
+                sql,
+                "select * from mock_table  where NAME like ? and Count >= ? and 名\u{212A}E = ?"
+            );
+            assert_eq!(args, vec![to_value!("%a%"), to_value!(2), to_value!(3)]);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_select_by_map_empty() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::select_by_map(&mut rb, to_value!{"id in": Vec::<i32>::new(), "limit": 1})
+                .await
+                .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select * from mock_table  where 1 = 0 limit 1");
+            assert_eq!(args, vec![]);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_update_by_map_operator() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let t = MockTable {
+                id: None,
+                name: Some("2".into()),
+                pc_link: None,
+                h5_link: None,
+                pc_banner_img: None,
+                h5_banner_img: None,
+                sort: None,
+                status: Some(2),
+                remark: None,
+                create_time: None,
+                version: None,
+                delete_flag: None,
+                count: 0,
+            };
+            let r = MockTable::update_by_map(
+                &mut rb,
+                &t,
+                to_value!{"id in":["1","2"], "version <":3},
+                true,
+            )
+            .await
+            .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            println!("{}", sql);
+            assert_eq!(
+                sql,
+                "update mock_table set name=?,status=?,count=?  where id in (?,?) and version < ?"
+            );
+            assert_eq!(
+                args,
+                vec![
+                    to_value!("2"),
+                    to_value!(2),
+                    to_value!(0u64),
+                    to_value!("1"),
+                    to_value!("2"),
+                    to_value!(3)
+                ]
+            );
+            let r = MockTable::update_by_map(&mut rb, &t, to_value!{"limit":1}, true).await;
+            assert_eq!(r.is_err(), true);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_delete_by_map_operator() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::delete_by_map(
+                &mut rb,
+                to_value!{"or":{"id":"1", "name is not null":()}},
+            )
+            .await
+            .unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "delete from mock_table  where (id = ? or name is not null)");
+            assert_eq!(args, vec![to_value!("1")]);
+            let r = MockTable::delete_by_map(&mut rb, to_value!{}).await;
+            assert_eq!(r.is_err(), true);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_count_by_map() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::count_by_map(&mut rb, to_value!{"status >": 1, "limit": 1})
+                .await
+                .unwrap();
+            assert_eq!(r, 1);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select count(1) as count from mock_table  where status > ?");
+            assert_eq!(args, vec![to_value!(1)]);
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    struct MockHookTable {
+        pub id: Option<String>,
+        pub name: Option<String>,
+    }
+    rbatis::crud!(MockHookTable {});
+
+    static HOOK_CALLS: AtomicI32 = AtomicI32::new(0);
+
+    impl rbatis::crud_traits::TableHooks for MockHookTable {
+        fn before_insert(&mut self) -> Result<(), Error> {
+            match &self.name {
+                Some(name) => {
+                    self.name = Some(name.trim().to_string());
+                    Ok(())
+                }
+                None => Err(Error::from("name can't be empty")),
+            }
+        }
+
+        fn after_insert(&self, _result: &ExecResult) -> Result<(), Error> {
+            HOOK_CALLS.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
+        fn before_update(&mut self) -> Result<(), Error> {
+            self.name = self.name.as_ref().map(|v| v.to_uppercase());
+            Ok(())
+        }
+
+        fn before_delete(condition: &Value) -> Result<(), Error> {
+            if condition["column_value"] == to_value!("0") {
+                return Err(Error::from("can't delete 0"));
+            }
+            Ok(())
+        }
+
+        fn after_delete(_condition: &Value, _result: &ExecResult) -> Result<(), Error> {
+            HOOK_CALLS.fetch_add(10, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_table_hooks() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let table = MockHookTable {
+                id: Some("1".to_string()),
+                name: Some(" a ".to_string()),
+            };
+            MockHookTable::insert(&mut rb, &table).await.unwrap();
+            let (_, args) = queue.pop().unwrap();
+            assert_eq!(args, vec![to_value!("1"), to_value!("a")]);
+            assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 1);
+            //before_insert error cancels the sql
+            let r = MockHookTable::insert(&mut rb, &MockHookTable { id: None, name: None }).await;
+            assert_eq!(r.is_err(), true);
+            assert_eq!(queue.pop().is_none(), true);
+
+            MockHookTable::update_by_column(&mut rb, &table, "id").await.unwrap();
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "update mock_hook_table set name=? where id = ?");
+            assert_eq!(args, vec![to_value!(" A "), to_value!("1")]);
+
+            MockHookTable::delete_by_column(&mut rb, "id", "1").await.unwrap();
+            assert_eq!(queue.pop().is_some(), true);
+            assert_eq!(HOOK_CALLS.load(Ordering::SeqCst), 11);
+            let r = MockHookTable::delete_by_column(&mut rb, "id", "0").await;
+            assert_eq!(r.is_err(), true);
+            assert_eq!(queue.pop().is_none(), true);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_exists_by_column() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::exists_by_column(&mut rb, "id", "1").await.unwrap();
+            assert_eq!(r, true);
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select 1 from mock_table where id = ? limit 1");
+            assert_eq!(args, vec![to_value!("1")]);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_aggregate_by_map() {
+        let f = async move {
+            let mut rb = RBatis::new();
+            let queue = Arc::new(SyncVec::new());
+            rb.set_intercepts(vec![Arc::new(MockIntercept::new(queue.clone()))]);
+            rb.init(MockDriver {}, "test").unwrap();
+            let r = MockTable::sum_by_map::<u64>(&mut rb, "count", to_value!{"status": 1}).await.unwrap();
+            assert_eq!(r, Some(1));
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select sum(count) as value from mock_table  where status = ?");
+            assert_eq!(args, vec![to_value!(1)]);
+            let r = MockTable::min_by_map::<u64>(&mut rb, "count", to_value!{}).await.unwrap();
+            assert_eq!(r, Some(1));
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "select min(count) as value from mock_table ");
+            let r = MockTable::max_by_map::<u64>(&mut rb, "count", to_value!{}).await.unwrap();
+            assert_eq!(r, Some(1));
+            let (sql, args) = queue.pop().unwrap();
+            assert_eq!(sql, "select max(count) as value from mock_table ");
+            assert_eq!(args, vec![]);
+            let r = MockTable::aggregate_by_map::<u64>(&mut rb, "COUNT", "*", to_value!{}).await.unwrap();
+            assert_eq!(r, Some(1));
+            let (sql, _) = queue.pop().unwrap();
+            assert_eq!(sql, "select COUNT(*) as value from mock_table ");
+            //not an aggregate function or an identifier
+            assert!(MockTable::aggregate_by_map::<u64>(&mut rb, "sleep", "count", to_value!{}).await.is_err());
+            assert!(MockTable::aggregate_by_map::<u64>(&mut rb, "sum", "count) from mock_table; --", to_value!{}).await.is_err());
+            assert!(MockTable::aggregate_by_map::<u64>(&mut rb, "sum", "*", to_value!{}).await.is_err());
+            assert!(rbatis::crud_traits::check_aggregate("avg", "t.count").is_ok());
+            assert!(rbatis::crud_traits::check_aggregate("avg", "1count").is_err());
+            assert!(queue.is_empty());
+        };
+        block_on(f);
+    }
+
     impl_select!(MockTable{select_from_table_name_by_id(id:&str,table_name:&str) => "`where id = #{id}`"});
 
     #[test]
@@ -26,7 +26,45 @@ mod test {
         let v = rbatis::decode::<A>(m).err().unwrap();
         assert_eq!(
             v.to_string(),
-            "invalid type: string \"\", expected i32, key = `aa`"
+            "decode row 0, column `aa`(String) into `decode_test::test::test_decode_type_fail::A` fail: invalid type: string \"\", expected i32"
+        );
+    }
+
+    #[test]
+    fn test_decode_type_fail_row() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+        pub struct A {
+            pub aa: i32,
+            pub bb: i32,
+        }
+        let m = Value::Array(vec![
+            to_value! {"aa": 1, "bb": 1},
+            to_value! {"aa": 2, "bb": 2},
+            to_value! {"aa": 3, "bb": "3"},
+            to_value! {"aa": 4, "bb": 4},
+        ]);
+        let v = rbatis::decode::<Vec<A>>(m).err().unwrap();
+        assert_eq!(
+            v.to_string(),
+            "decode row 2, column `bb`(String) into `alloc::vec::Vec<decode_test::test::test_decode_type_fail_row::A>` fail: invalid type: string \"3\", expected i32"
+        );
+    }
+
+    #[test]
+    fn test_decode_missing_field_row() {
+        #[derive(serde::Serialize, serde::Deserialize, Debug)]
+        pub struct A {
+            pub aa: i32,
+            pub bb: i32,
+        }
+        let m = Value::Array(vec![
+            to_value! {"aa": 1, "bb": 1},
+            to_value! {"aa": 2},
+        ]);
+        let v = rbatis::decode::<Vec<Option<A>>>(m).err().unwrap();
+        assert_eq!(
+            v.to_string(),
+            "decode row 1 into `alloc::vec::Vec<core::option::Option<decode_test::test::test_decode_missing_field_row::A>>` fail: missing field `bb`"
         );
     }
 
@@ -39,7 +77,7 @@ mod test {
         let v = rbatis::decode::<i64>(m).err().unwrap();
         assert_eq!(
             v.to_string(),
-            "invalid type: floating point `0.0`, expected i64"
+            "decode row 0, column `aa`(F64) into `i64` fail: invalid type: floating point `0.0`, expected i64"
         );
     }
 
@@ -111,4 +149,53 @@ mod test {
             rbs::from_value(rbs::to_value!(datetime.clone())).unwrap();
         assert_eq!(datetime, datetime_new);
     }
+
+    #[test]
+    fn test_decode_tuple_and_scalar() {
+        #[derive(Deserialize, Debug, PartialEq)]
+        pub struct UserId(i64);
+        let rows = Value::Array(vec![
+            to_value! {"id": 1i64, "name": "a"},
+            to_value! {"id": 2i64, "name": "b"},
+        ]);
+        let v: Vec<(i64, String)> = rbatis::decode(rows.clone()).unwrap();
+        assert_eq!(v, vec![(1, "a".to_string()), (2, "b".to_string())]);
+        let v: (i64, String) = rbatis::decode(Value::Array(vec![to_value! {"id": 1i64, "name": "a"}])).unwrap();
+        assert_eq!(v, (1, "a".to_string()));
+        let v: std::collections::HashMap<i64, String> = rbatis::decode_pairs(rows).unwrap();
+        assert_eq!(v.get(&2).unwrap(), "b");
+
+        let ids = Value::Array(vec![to_value! {"id": 1i64}, to_value! {"id": 2i64}]);
+        let v: Vec<i64> = rbatis::decode(ids.clone()).unwrap();
+        assert_eq!(v, vec![1, 2]);
+        let v: Vec<UserId> = rbatis::decode(ids).unwrap();
+        assert_eq!(v, vec![UserId(1), UserId(2)]);
+        let v: Option<UserId> = rbatis::decode(Value::Array(vec![to_value! {"id": 1i64}])).unwrap();
+        assert_eq!(v, Some(UserId(1)));
+        let v: Option<UserId> = rbatis::decode(Value::Array(vec![])).unwrap();
+        assert_eq!(v, None);
+    }
+
+    #[test]
+    fn test_decode_map_single_row() {
+        use std::collections::HashMap;
+        //a map is the row by the column names, even if the row has two columns
+        let row = Value::Array(vec![to_value! {"id": 1i64, "name": "a"}]);
+        let v: HashMap<String, Value> = rbatis::decode(row.clone()).unwrap();
+        assert_eq!(v.len(), 2);
+        assert_eq!(v["id"], Value::I64(1));
+        assert_eq!(v["name"], Value::String("a".to_string()));
+        //the pairs of a single row
+        let v: HashMap<i64, String> = rbatis::decode_pairs(row).unwrap();
+        assert_eq!(v, HashMap::from([(1, "a".to_string())]));
+        let rows = Value::Array(vec![
+            to_value! {"id": 1i64, "name": "a"},
+            to_value! {"id": 2i64, "name": "b"},
+        ]);
+        assert!(rbatis::decode::<HashMap<String, Value>>(rows).is_err());
+        let v: HashMap<i64, String> = rbatis::decode_pairs(Value::Array(vec![])).unwrap();
+        assert!(v.is_empty());
+        let e = rbatis::decode_pairs::<HashMap<i64, String>>(Value::Array(vec![to_value! {"id": 1i64}])).unwrap_err();
+        assert_eq!(e.to_string(), "decode row 0 into `std::collections::hash::map::HashMap<i64, alloc::string::String>` fail: the pairs need 2 columns");
+    }
 }
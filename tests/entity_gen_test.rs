@@ -0,0 +1,141 @@
+#[cfg(test)]
+mod test {
+    use rbatis::entity_gen;
+    use rbatis::rbdc::{Bytes, Date, DateTime, Decimal, Json, Uuid};
+    use rbatis::RBatis;
+    use rbdc::rt::block_on;
+    use rbdc_sqlite::driver::SqliteDriver;
+
+    //the generated code of `sys_user`
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    pub struct SysUser {
+        pub id: Option<i64>,
+        pub name: String,
+        pub enable: Option<i64>,
+        pub price: Option<Decimal>,
+        pub create_time: Option<DateTime>,
+        pub birthday: Option<Date>,
+        pub data: Option<Json>,
+        pub uid: Option<Uuid>,
+        pub avatar: Option<Bytes>,
+        pub score: Option<f64>,
+        pub r#type: Option<String>,
+        #[serde(rename = "updateTime")]
+        pub update_time: Option<DateTime>,
+    }
+    rbatis::crud!(SysUser {}, "sys_user");
+
+    #[test]
+    fn test_entity_gen_sqlite() {
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            conn.exec(
+                "create table sys_user (id integer primary key, name varchar(50) not null, enable boolean, price decimal(18,4), create_time datetime, birthday date, data json, uid uuid, avatar blob, score double, type text, updateTime timestamp)",
+                vec![],
+            )
+            .await
+            .unwrap();
+            conn.exec("create table sys_role (id text not null primary key)", vec![])
+                .await
+                .unwrap();
+            let code = entity_gen::generate(&conn, &["sys_user".to_string()])
+                .await
+                .unwrap();
+            assert_eq!(
+                code,
+                r#"use rbatis::rbdc::{Bytes, Date, DateTime, Decimal, Json, Uuid};
+
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct SysUser {
+    pub id: Option<i64>,
+    pub name: String,
+    pub enable: Option<i64>,
+    pub price: Option<Decimal>,
+    pub create_time: Option<DateTime>,
+    pub birthday: Option<Date>,
+    pub data: Option<Json>,
+    pub uid: Option<Uuid>,
+    pub avatar: Option<Bytes>,
+    pub score: Option<f64>,
+    pub r#type: Option<String>,
+    #[serde(rename = "updateTime")]
+    pub update_time: Option<DateTime>,
+}
+rbatis::crud!(SysUser {}, "sys_user");
+"#
+            );
+            //the generated struct decode the rows
+            conn.exec(
+                "insert into sys_user values (1, 'a', 1, 1.5, '2024-01-02 03:04:05', '2024-01-02', '{\"a\":1}', '5a2b3c4d-0000-4000-8000-000000000001', x'0102ff', 2.5, 't', '2024-01-02 03:04:05')",
+                vec![],
+            )
+            .await
+            .unwrap();
+            conn.exec("insert into sys_user (name) values ('b')", vec![])
+                .await
+                .unwrap();
+            let rows = SysUser::select_all(&conn).await.unwrap();
+            assert_eq!(rows.len(), 2);
+            let row = &rows[0];
+            assert_eq!(row.id, Some(1));
+            assert_eq!(row.name, "a");
+            assert_eq!(row.enable, Some(1));
+            assert_eq!(row.price.as_ref().unwrap().to_string(), "1.5");
+            assert_eq!(row.birthday.as_ref().unwrap().to_string(), "2024-01-02");
+            assert_eq!(row.uid.as_ref().unwrap().to_string(), "5a2b3c4d-0000-4000-8000-000000000001");
+            assert_eq!(row.avatar.as_ref().unwrap().as_ref(), &[1u8, 2, 255]);
+            assert_eq!(row.score, Some(2.5));
+            assert_eq!(row.r#type.as_deref(), Some("t"));
+            assert!(row.create_time.is_some());
+            assert!(row.update_time.is_some());
+            assert!(row.data.is_some());
+            assert_eq!(rows[1].name, "b");
+            assert!(rows[1].avatar.is_none());
+            let code = entity_gen::generate(&conn, &[]).await.unwrap();
+            assert!(code.contains("pub struct SysRole {\n    pub id: String,\n}"));
+            assert!(code.contains("pub struct SysUser {"));
+            assert!(entity_gen::generate(&conn, &["not_exists".to_string()])
+                .await
+                .is_err());
+        };
+        block_on(f);
+        assert_eq!(entity_gen::rust_type("mysql", "tinyint(1)"), "i8");
+        assert_eq!(entity_gen::rust_type("postgres", "boolean"), "bool");
+        assert_eq!(entity_gen::rust_type("mysql", "int(10) unsigned"), "u32");
+        assert_eq!(entity_gen::rust_type("postgres", "character varying(50)"), "String");
+        assert_eq!(entity_gen::rust_type("postgres", "timestamp with time zone"), "DateTime");
+        assert_eq!(entity_gen::rust_type("postgres", "integer"), "i32");
+        assert_eq!(entity_gen::rust_type("mssql", "uniqueidentifier"), "Uuid");
+        assert_eq!(entity_gen::rust_type("mssql", "nvarchar(50)"), "String");
+        assert_eq!(entity_gen::rust_type("postgres", "bytea"), "Bytes");
+    }
+
+    #[test]
+    fn test_entity_gen_keywords() {
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            conn.exec(
+                "create table kw_test (id integer primary key, try text, box text, yield text, gen text, self text)",
+                vec![],
+            )
+            .await
+            .unwrap();
+            let code = entity_gen::generate(&conn, &["kw_test".to_string()])
+                .await
+                .unwrap();
+            assert!(code.contains(
+                "    pub r#try: Option<String>,
+    pub r#box: Option<String>,
+    pub r#yield: Option<String>,
+    pub r#gen: Option<String>,
+"
+            ));
+            assert!(code.contains("    pub self_: Option<String>,"));
+        };
+        block_on(f);
+    }
+}
@@ -0,0 +1,204 @@
+#[cfg(test)]
+mod test {
+    use rbatis::id_generator::IdGenerator;
+    use rbatis::object_id::ObjectId;
+    use rbatis::snowflake::Snowflake;
+    use rbatis::ulid::Ulid;
+    use rbatis::uuid_v7::UuidV7;
+    use rbatis::RBatis;
+    use rbdc::rt::block_on;
+    use rbdc_sqlite::driver::SqliteDriver;
+    use rbs::Value;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_uuid_v7() {
+        let g = UuidV7::new();
+        let ids: Vec<String> = (0..10000).map(|_| g.generate()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[1] > pair[0], "{} > {}", pair[1], pair[0]);
+        }
+        let id = &ids[0];
+        assert_eq!(id.len(), 36);
+        assert_eq!(&id[14..15], "7");
+        assert!(matches!(&id[19..20], "8" | "9" | "a" | "b"));
+        //the same `Value::String` of the string generators
+        assert_eq!(g.generate_id().as_str().unwrap().len(), 36);
+        assert_eq!(Ulid::new().generate_id().as_str().unwrap().len(), 26);
+    }
+
+    #[test]
+    fn test_ulid() {
+        let g = Ulid::new();
+        let ids: Vec<String> = (0..10000).map(|_| g.generate()).collect();
+        for pair in ids.windows(2) {
+            assert!(pair[1] > pair[0], "{} > {}", pair[1], pair[0]);
+        }
+        assert_eq!(ids[0].len(), 26);
+        assert!(ids[0].chars().all(|c| "0123456789ABCDEFGHJKMNPQRSTVWXYZ".contains(c)));
+        //the first 10 chars are the milliseconds
+        let millis = ids[0][..10]
+            .chars()
+            .fold(0u64, |v, c| v * 32 + "0123456789ABCDEFGHJKMNPQRSTVWXYZ".find(c).unwrap() as u64);
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_millis() as u64;
+        assert!(now - millis < 60000);
+    }
+
+    #[test]
+    fn test_id_generator() {
+        let snowflake: Arc<dyn IdGenerator> = Arc::new(Snowflake::default());
+        assert!(snowflake.generate_id().as_i64().unwrap() > 0);
+        let object_id: Arc<dyn IdGenerator> = Arc::new(ObjectId::new());
+        assert_eq!(object_id.generate_id().as_str().unwrap().len(), 24);
+        assert!(object_id.generate_i64() >= 0);
+        let mut rb = RBatis::new();
+        rb.task_id_generator = Arc::new(Ulid::new());
+        assert!(rb.task_id_generator.generate_i64() >= 0);
+        //the `Arc<Snowflake>` of the old task id generator
+        rb.task_id_generator = Arc::new(Snowflake::new(1, 1, 1));
+        #[allow(deprecated)]
+        let id = rb.task_id_generator.generate();
+        assert!(id > 0);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+    pub struct Activity {
+        pub id: Option<String>,
+        pub name: Option<String>,
+    }
+    rbatis::crud!(Activity {});
+
+    #[test]
+    fn test_insert_assign_id() {
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            rb.set_id_generator("activity", "id", Arc::new(Ulid::new()));
+            let conn = rb.acquire().await.unwrap();
+            conn.exec("create table activity (id text primary key, name text)", vec![])
+                .await
+                .unwrap();
+            let table = Activity {
+                id: None,
+                name: Some("a".to_string()),
+            };
+            let r = Activity::insert(&conn, &table).await.unwrap();
+            assert_eq!(r.last_insert_id.as_array().unwrap().len(), 1);
+            let id = r.last_insert_id[0].as_str().unwrap().to_string();
+            assert_eq!(id.len(), 26);
+            //the not empty id is kept
+            let table = Activity {
+                id: Some("b".to_string()),
+                name: Some("b".to_string()),
+            };
+            Activity::insert(&conn, &table).await.unwrap();
+            let mut tables = vec![
+                Activity {
+                    id: Some("".to_string()),
+                    name: Some("c".to_string()),
+                },
+                Activity {
+                    id: None,
+                    name: Some("d".to_string()),
+                },
+            ];
+            Activity::insert_batch_returning(&conn, &mut tables, "id", 10)
+                .await
+                .unwrap();
+            assert_eq!(tables[0].id.as_ref().unwrap().len(), 26);
+            assert!(tables[1].id > tables[0].id);
+            let rows = Activity::select_all(&conn).await.unwrap();
+            let ids: Vec<String> = rows.into_iter().map(|v| v.id.unwrap()).collect();
+            assert_eq!(
+                ids,
+                vec![
+                    id,
+                    "b".to_string(),
+                    tables[0].id.clone().unwrap(),
+                    tables[1].id.clone().unwrap()
+                ]
+            );
+            rb.remove_id_generator("activity");
+            assert!(rb.get_id_generator("activity").is_none());
+        };
+        block_on(f);
+    }
+
+    #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, rbatis::Table)]
+    #[table(name = "event")]
+    pub struct Event {
+        #[column(pk)]
+        pub code: Option<String>,
+        pub name: Option<String>,
+    }
+
+    #[test]
+    fn test_insert_assign_table_pk() {
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            rb.set_table_id_generator::<Event>(Arc::new(UuidV7::new()));
+            assert_eq!(rb.get_id_generator("event").unwrap().column, "code");
+            let conn = rb.acquire().await.unwrap();
+            conn.exec("create table event (code text primary key, name text)", vec![])
+                .await
+                .unwrap();
+            let table = Event {
+                code: None,
+                name: Some("a".to_string()),
+            };
+            Event::insert(&conn, &table).await.unwrap();
+            let rows = Event::select_all(&conn).await.unwrap();
+            assert_eq!(rows[0].code.as_ref().unwrap().len(), 36);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_insert_batch_assign_id() {
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            rb.set_id_generator("activity", "id", Arc::new(Ulid::new()));
+            let conn = rb.acquire().await.unwrap();
+            conn.exec("create table activity (id text primary key, name text)", vec![])
+                .await
+                .unwrap();
+            let tables = vec![
+                Activity {
+                    id: None,
+                    name: Some("a".to_string()),
+                },
+                Activity {
+                    id: Some("b".to_string()),
+                    name: Some("b".to_string()),
+                },
+                Activity {
+                    id: None,
+                    name: Some("c".to_string()),
+                },
+            ];
+            //2 batches, the ids of all the batches are returned
+            let r = Activity::insert_batch(&conn, &tables, 2).await.unwrap();
+            assert_eq!(r.rows_affected, 3);
+            let ids: Vec<String> = r
+                .last_insert_id
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|v| v.as_str().unwrap().to_string())
+                .collect();
+            assert_eq!(ids.len(), 3);
+            assert_eq!(ids[0].len(), 26);
+            assert_eq!(ids[1], "b");
+            assert!(ids[2] > ids[0]);
+            let rows = Activity::select_all(&conn).await.unwrap();
+            let selected: Vec<String> = rows.into_iter().map(|v| v.id.unwrap()).collect();
+            assert_eq!(selected, ids);
+        };
+        block_on(f);
+    }
+}
@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod test {
+    use rbatis::migration::{split_sql, Migration, Migrations};
+    use rbatis::{schema, RBatis};
+    use rbdc::rt::block_on;
+    use rbdc_sqlite::driver::SqliteDriver;
+
+    #[test]
+    fn test_split_sql() {
+        let sql = "create table a (name text default 'a;b'); -- c;\n/* d; */insert into a values ('x');\ncreate function f() returns int as $$ select 1; $$ language sql;\nselect 1\nGO\nselect 2";
+        assert_eq!(
+            split_sql(sql),
+            vec![
+                "create table a (name text default 'a;b')",
+                "insert into a values ('x')",
+                "create function f() returns int as $$ select 1; $$ language sql",
+                "select 1",
+                "select 2",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_split_sql_blocks() {
+        let sql = "create function f() returns int as $body$ select 1; $body$ language sql;\nselect $1;\ncreate trigger t after insert on a begin update a set n = case when n > 0 then 1 else 0 end; delete from b; end;\nbegin transaction;\ncreate procedure p() begin if 1 then select 1; end if; end;\ncommit;";
+        assert_eq!(
+            split_sql(sql),
+            vec![
+                "create function f() returns int as $body$ select 1; $body$ language sql",
+                "select $1",
+                "create trigger t after insert on a begin update a set n = case when n > 0 then 1 else 0 end; delete from b; end",
+                "begin transaction",
+                "create procedure p() begin if 1 then select 1; end if; end",
+                "commit",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_migration_run_in_tx() {
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            let migrations = rbatis::embed_migrations!("tests/migrations").unwrap();
+            //rolled back by the caller
+            let tx = conn.begin().await.unwrap();
+            assert_eq!(migrations.run(&tx).await.unwrap(), vec![1, 2]);
+            tx.rollback().await.unwrap();
+            assert!(!schema::tables(&tx).await.unwrap().contains(&"user".to_string()));
+
+            let tx = tx.begin().await.unwrap();
+            assert_eq!(migrations.run(&tx).await.unwrap(), vec![1, 2]);
+            tx.commit().await.unwrap();
+            assert_eq!(migrations.history(&tx).await.unwrap().len(), 2);
+            assert_eq!(migrations.run(&tx).await.unwrap(), Vec::<u64>::new());
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_migration_run() {
+        let f = async move {
+            //the migrations run on their own connection, an in-memory database is per connection
+            let path = std::env::temp_dir().join("rbatis_migration_test.db");
+            let _ = std::fs::remove_file(&path);
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, &format!("sqlite://{}", path.display())).unwrap();
+            let conn = rb.acquire().await.unwrap();
+            let migrations = rbatis::embed_migrations!("tests/migrations").unwrap();
+            assert_eq!(migrations.migrations().len(), 2);
+            assert_eq!(migrations.migrations()[1].name, "add_user_email");
+            let from_dir = Migrations::from_dir("tests/migrations").unwrap();
+            assert_eq!(from_dir.migrations(), migrations.migrations());
+            assert_eq!(migrations.run_with_tx(&rb).await.unwrap(), vec![1, 2]);
+            let email: String = conn
+                .query_decode("select email from user where id = 1", vec![])
+                .await
+                .unwrap();
+            assert_eq!(email, "admin@localhost");
+            //applied
+            assert_eq!(migrations.run_with_tx(&rb).await.unwrap(), Vec::<u64>::new());
+            let history = migrations.history(&conn).await.unwrap();
+            assert_eq!(history.len(), 2);
+            assert_eq!(history[0].checksum, migrations.migrations()[0].checksum);
+
+            //a failed migration is rolled back
+            let mut list = migrations.migrations().to_vec();
+            list.push(Migration::new(3, "fail", "create table t3 (id int); insert into not_exists values (1);"));
+            let e = Migrations::new(list.clone()).unwrap().run_with_tx(&rb).await.unwrap_err();
+            assert!(e.to_string().starts_with("migration V3__fail fail:"), "{}", e);
+            assert!(!schema::tables(&conn).await.unwrap().contains(&"t3".to_string()));
+            assert_eq!(migrations.history(&conn).await.unwrap().len(), 2);
+
+            //changed after applied
+            list.pop();
+            list[0] = Migration::new(1, "create_user", "create table user (id integer primary key);");
+            let e = Migrations::new(list).unwrap().run_with_tx(&rb).await.unwrap_err();
+            assert!(e.to_string().contains("V1__create_user is changed"), "{}", e);
+
+            let r = Migrations::new(vec![Migration::new(1, "a", ""), Migration::new(1, "b", "")]);
+            assert!(r.is_err());
+        };
+        block_on(f);
+    }
+}
@@ -0,0 +1,6 @@
+-- the users; of the app
+create table user (
+    id integer primary key,
+    name text not null default 'a;b'
+);
+insert into user (id, name) values (1, 'admin');
@@ -0,0 +1,3 @@
+/* the email; column */
+alter table user add email text;
+update user set email = 'admin@localhost' where id = 1;
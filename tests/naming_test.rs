@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod test {
+    use rbatis::crud_traits::to_columns;
+    use rbatis::decode::NamingStrategy;
+    use rbatis::RBatis;
+    use rbdc::rt::block_on;
+    use rbdc_sqlite::driver::SqliteDriver;
+    use rbs::Value;
+
+    #[derive(serde::Serialize, serde::Deserialize, Clone, Debug, PartialEq)]
+    #[serde(rename_all = "camelCase")]
+    pub struct User {
+        pub user_id: Option<i64>,
+        pub create_time: Option<String>,
+    }
+    rbatis::crud!(User {}, "naming_user");
+
+    #[test]
+    fn test_naming_convert() {
+        let naming = NamingStrategy::SnakeToCamel;
+        assert_eq!(naming.to_field("create_time", &[]), "createTime");
+        assert_eq!(naming.to_field("CREATE_TIME", &[]), "createTime");
+        assert_eq!(naming.to_field("ID", &[]), "id");
+        assert_eq!(naming.to_field("createTime", &[]), "createTime");
+        assert_eq!(naming.to_column("userID"), "user_id");
+        let naming = NamingStrategy::CamelToSnake;
+        assert_eq!(naming.to_field("createTime", &[]), "create_time");
+        assert_eq!(naming.to_column("create_time"), "createTime");
+        let naming = NamingStrategy::CaseInsensitive;
+        assert_eq!(naming.to_field("CREATETIME", &["userId", "createTime"]), "createTime");
+        assert_eq!(naming.to_field("CREATE_TIME", &["userId", "createTime"]), "CREATE_TIME");
+        assert_eq!(naming.to_column("createTime"), "createTime");
+    }
+
+    #[test]
+    fn test_naming_strategy() {
+        let rows = Value::Array(vec![rbs::value! {
+            "USER_ID": 1,
+            "create_time": "2024",
+        }]);
+        let users: Vec<User> =
+            rbatis::decode_naming(rows, NamingStrategy::SnakeToCamel).unwrap();
+        assert_eq!(
+            users,
+            vec![User {
+                user_id: Some(1),
+                create_time: Some("2024".to_string()),
+            }]
+        );
+        let columns = to_columns(
+            rbs::value!(&users[0]),
+            &[],
+            NamingStrategy::SnakeToCamel,
+            None,
+        );
+        assert_eq!(
+            columns,
+            rbs::value! {
+                "user_id": 1i64,
+                "create_time": "2024",
+            }
+        );
+    }
+
+    #[test]
+    fn test_naming_case_insensitive() {
+        let rows = Value::Array(vec![rbs::value! {
+            "USERID": 1,
+            "CREATETIME": "2024",
+        }]);
+        let users: Vec<User> =
+            rbatis::decode_naming(rows, NamingStrategy::CaseInsensitive).unwrap();
+        assert_eq!(
+            users,
+            vec![User {
+                user_id: Some(1),
+                create_time: Some("2024".to_string()),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_naming_case_insensitive_sqlite() {
+        block_on(async move {
+            let mut rb = RBatis::new();
+            rb.naming_strategy = NamingStrategy::CaseInsensitive;
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            conn.exec(
+                "create table naming_user (USERID INTEGER PRIMARY KEY AUTOINCREMENT, CREATETIME TEXT)",
+                vec![],
+            )
+            .await
+            .unwrap();
+            let mut user = User {
+                user_id: None,
+                create_time: Some("2024".to_string()),
+            };
+            let result = User::insert_returning(&conn, &mut user, "userId")
+                .await
+                .unwrap();
+            assert_eq!(result.rows_affected, 1);
+            assert_eq!(user.user_id, Some(1));
+            assert_eq!(user.create_time, Some("2024".to_string()));
+            let users = User::select_all(&conn).await.unwrap();
+            assert_eq!(users, vec![user]);
+        });
+    }
+}
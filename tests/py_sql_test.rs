@@ -137,15 +137,12 @@ mod test {
     struct MockConnection {}
 
     impl Connection for MockConnection {
-        fn get_rows(
-            &mut self,
-            sql: &str,
-            params: Vec<Value>,
-        ) -> BoxFuture<Result<Vec<Box<dyn Row>>, Error>> {
+        fn exec_rows(&mut self, sql: &str, params: Vec<Value>) -> BoxFuture<'_, Result<futures::stream::BoxStream<'_, Result<Box<dyn Row>, Error>>, Error>> {
             let sql = sql.to_string();
             Box::pin(async move {
                 let data = Box::new(MockRow { sql: sql, count: 1 }) as Box<dyn Row>;
-                Ok(vec![data])
+                let s: futures::stream::BoxStream<'_, Result<Box<dyn Row>, Error>> = Box::pin(futures::stream::iter(vec![Ok(data)]));
+                Ok(s)
             })
         }
 
@@ -208,4 +208,91 @@ mod test {
         let name = field_key!(MockTable::base::pc_banner_img);
         assert_eq!(name, "pc_banner_img");
     }
+
+    #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, rbatis::Table)]
+    #[table(name = "load_post")]
+    pub struct Post {
+        pub id: i64,
+        pub title: String,
+        #[serde(skip)]
+        pub comments: Vec<Comment>,
+    }
+
+    #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    pub struct Comment {
+        pub id: Option<i64>,
+        #[serde(rename = "postId")]
+        pub post_id: Option<i64>,
+        pub content: String,
+        #[serde(skip)]
+        pub post: Option<Post>,
+    }
+    rbatis::crud!(Comment {}, "load_comment");
+
+    #[test]
+    fn test_table_load() {
+        use rbatis::{table_load_many, table_load_one, RBatis};
+        use rbdc_sqlite::driver::SqliteDriver;
+        rbdc::rt::block_on(async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            for sql in [
+                "create table load_post (id INTEGER PRIMARY KEY, title TEXT)",
+                "create table load_comment (id INTEGER PRIMARY KEY, postId INTEGER, content TEXT)",
+                "insert into load_post values (1, 'a'), (2, 'b'), (3, 'c')",
+                "insert into load_comment values (1, 1, 'a1'), (2, 1, 'a2'), (3, 2, 'b1'), (4, null, 'x')",
+            ] {
+                conn.exec(sql, vec![]).await.unwrap();
+            }
+            let mut posts = Post::select_all(&conn).await.unwrap();
+            assert_eq!(posts.len(), 3);
+            let groups: HashMap<i64, Vec<Comment>> =
+                table_load_many!(&conn, &posts, id, Comment, post_id, "postId")
+                    .await
+                    .unwrap();
+            assert_eq!(groups.len(), 2);
+            assert_eq!(groups[&1].len(), 2);
+
+            table_load_many!(&conn, &mut posts, id, Comment, post_id, "postId" => comments)
+                .await
+                .unwrap();
+            let contents: Vec<Vec<&str>> = posts
+                .iter()
+                .map(|v| v.comments.iter().map(|c| c.content.as_str()).collect())
+                .collect();
+            assert_eq!(contents, vec![vec!["a1", "a2"], vec!["b1"], vec![]]);
+            //the parents of the same key take the same children
+            let mut same = vec![posts[0].clone(), posts[0].clone()];
+            table_load_many!(&conn, &mut same, id, Comment, post_id, "postId" => comments)
+                .await
+                .unwrap();
+            assert_eq!(same[0].comments.len(), 2);
+            assert_eq!(same[1].comments, same[0].comments);
+            //the keys more than a chunk
+            let many: Vec<Post> = (1..=rbatis::utils::table_util::LOAD_CHUNK_SIZE as i64 * 2 + 1)
+                .map(|id| Post {
+                    id,
+                    title: String::new(),
+                    comments: vec![],
+                })
+                .collect();
+            let groups: HashMap<i64, Vec<Comment>> =
+                table_load_many!(&conn, &many, id, Comment, post_id, "postId")
+                    .await
+                    .unwrap();
+            assert_eq!(groups.len(), 2);
+            assert_eq!(groups[&1].len(), 2);
+
+            let mut comments = Comment::select_all(&conn).await.unwrap();
+            table_load_one!(&conn, &mut comments, post_id, Post, id, PostColumns::ID => post)
+                .await
+                .unwrap();
+            let titles: Vec<Option<&str>> = comments
+                .iter()
+                .map(|c| c.post.as_ref().map(|p| p.title.as_str()))
+                .collect();
+            assert_eq!(titles, vec![Some("a"), Some("a"), Some("b"), None]);
+        });
+    }
 }
@@ -0,0 +1,133 @@
+#[cfg(test)]
+mod test {
+    use rbatis::{RBatis, ResultMap};
+    use rbdc::rt::block_on;
+    use rbdc_sqlite::driver::SqliteDriver;
+    use rbs::Value;
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    pub struct Author {
+        pub id: i64,
+        pub name: String,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    pub struct Comment {
+        pub id: i64,
+        pub body: String,
+    }
+
+    #[derive(serde::Deserialize, Debug, PartialEq)]
+    pub struct Post {
+        pub id: i64,
+        #[serde(rename = "title__draft")]
+        pub title_draft: String,
+        pub author: Author,
+        pub comments: Vec<Comment>,
+    }
+
+    #[test]
+    fn test_result_map_sqlite() {
+        block_on(async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            for sql in [
+                "create table rm_author (id INTEGER PRIMARY KEY, name TEXT)",
+                "create table rm_post (id INTEGER PRIMARY KEY, author_id INTEGER, title__draft TEXT)",
+                "create table rm_comment (id INTEGER PRIMARY KEY, post_id INTEGER, body TEXT)",
+                "insert into rm_author values (1, 'a'), (2, 'b')",
+                "insert into rm_post values (1, 1, 'p1'), (2, 2, 'p2')",
+                "insert into rm_comment values (1, 1, 'x'), (2, 1, 'y')",
+            ] {
+                conn.exec(sql, vec![]).await.unwrap();
+            }
+            let rows = conn
+                .query(
+                    "select p.id, p.title__draft, a.id as author__id, a.name as author__name, c.id as comments__id, c.body as comments__body \
+                     from rm_post p join rm_author a on p.author_id = a.id left join rm_comment c on c.post_id = p.id order by p.id, c.id",
+                    vec![],
+                )
+                .await
+                .unwrap();
+            let posts: Vec<Post> = ResultMap::new()
+                .id("id")
+                .association("author", ResultMap::new())
+                .collection("comments", ResultMap::new())
+                .decode(rows)
+                .unwrap();
+            assert_eq!(
+                posts,
+                vec![
+                    Post {
+                        id: 1,
+                        title_draft: "p1".to_string(),
+                        author: Author {
+                            id: 1,
+                            name: "a".to_string(),
+                        },
+                        comments: vec![
+                            Comment {
+                                id: 1,
+                                body: "x".to_string(),
+                            },
+                            Comment {
+                                id: 2,
+                                body: "y".to_string(),
+                            },
+                        ],
+                    },
+                    Post {
+                        id: 2,
+                        title_draft: "p2".to_string(),
+                        author: Author {
+                            id: 2,
+                            name: "b".to_string(),
+                        },
+                        comments: vec![],
+                    },
+                ]
+            );
+        });
+    }
+
+    #[test]
+    fn test_result_map_group_integers() {
+        #[derive(serde::Deserialize, Debug)]
+        pub struct Post {
+            pub id: i64,
+            pub comments: Vec<Comment>,
+        }
+        //the same id decoded as the different integer types
+        let rows = Value::Array(vec![
+            rbs::value! {"id": Value::I32(1), "comments__id": 1, "comments__body": "x"},
+            rbs::value! {"id": Value::I64(1), "comments__id": 2, "comments__body": "y"},
+            rbs::value! {"id": Value::U64(1), "comments__id": 3, "comments__body": "z"},
+        ]);
+        let posts: Vec<Post> = ResultMap::new()
+            .id("id")
+            .collection("comments", ResultMap::new())
+            .decode(rows)
+            .unwrap();
+        assert_eq!(posts.len(), 1);
+        assert_eq!(posts[0].id, 1);
+        assert_eq!(posts[0].comments.len(), 3);
+    }
+
+    #[test]
+    fn test_result_map_missing_id() {
+        let rows = Value::Array(vec![
+            rbs::value! {"id": 1, "comments__body": "x"},
+            rbs::value! {"id": 2, "comments__body": "y"},
+        ]);
+        let err = ResultMap::new()
+            .id("id")
+            .collection("comments", ResultMap::new().id("id"))
+            .decode::<Value>(rows)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "decode ResultMap fail: the id column `comments__id` not in the row"
+        );
+    }
+}
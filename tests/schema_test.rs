@@ -0,0 +1,284 @@
+#[cfg(test)]
+mod test {
+    use rbatis::schema::{ColumnInfo, ForeignKeyInfo, IndexInfo};
+    use rbatis::{schema, RBatis};
+    use rbdc::rt::block_on;
+    use rbdc_sqlite::driver::SqliteDriver;
+
+    #[test]
+    fn test_schema_sqlite() {
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            for sql in [
+                "create table author (id integer primary key, name text not null)",
+                "create table post (id integer, version integer, author_id integer references author(id), title varchar(50) default 'none', primary key (id, version))",
+                "create unique index idx_post_title on post (title, author_id)",
+            ] {
+                conn.exec(sql, vec![]).await.unwrap();
+            }
+            assert_eq!(schema::tables(&conn).await.unwrap(), vec!["author", "post"]);
+            let post = schema::table_info(&conn, "post").await.unwrap().unwrap();
+            assert_eq!(post.primary_keys, vec!["id", "version"]);
+            assert_eq!(
+                post.column("title"),
+                Some(&ColumnInfo {
+                    name: "title".to_string(),
+                    column_type: "varchar(50)".to_string(),
+                    nullable: true,
+                    default: Some("'none'".to_string()),
+                })
+            );
+            assert_eq!(
+                post.indexes,
+                vec![IndexInfo {
+                    name: "idx_post_title".to_string(),
+                    columns: vec!["title".to_string(), "author_id".to_string()],
+                    unique: true,
+                }]
+            );
+            assert_eq!(
+                post.foreign_keys,
+                vec![ForeignKeyInfo {
+                    name: "0".to_string(),
+                    columns: vec!["author_id".to_string()],
+                    ref_table: "author".to_string(),
+                    ref_columns: vec!["id".to_string()],
+                    on_delete: None,
+                }]
+            );
+            let author = schema::table_info(&conn, "author").await.unwrap().unwrap();
+            assert!(!author.column("name").unwrap().nullable);
+            assert_eq!(schema::table_info(&conn, "not_exists").await.unwrap(), None);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_table_sync_report() {
+        use rbatis::table_sync::{is_same_type, ColumnMismatch, SqliteTableMapper};
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            let mapper = &SqliteTableMapper {};
+            let table = rbs::value! {"id": "INTEGER", "name": "TEXT"};
+            let report = RBatis::sync(&conn, mapper, &table, "rb_user").await.unwrap();
+            assert!(report.created);
+            assert_eq!(report.added, vec!["id", "name"]);
+
+            assert_eq!(report.ddl, vec!["CREATE TABLE rb_user (id INTEGER,name TEXT);"]);
+
+            let table = rbs::value! {"id": "INTEGER", "name": "VARCHAR(50)", "age": "INT8"};
+            //dry run
+            let report = RBatis::sync_plan(&conn, mapper, &table, "rb_user").await.unwrap();
+            assert_eq!(report.ddl, vec!["alter table rb_user add age INT8 ;"]);
+            assert_eq!(schema::columns(&conn, "rb_user").await.unwrap().len(), 2);
+            let report = RBatis::sync(&conn, mapper, &table, "rb_user").await.unwrap();
+            assert!(!report.created);
+            assert_eq!(report.added, vec!["age"]);
+            assert_eq!(report.skipped, vec!["id"]);
+            assert_eq!(
+                report.mismatched,
+                vec![ColumnMismatch {
+                    column: "name".to_string(),
+                    expected: "VARCHAR(50)".to_string(),
+                    actual: "TEXT".to_string(),
+                }]
+            );
+            let report = RBatis::sync(&conn, mapper, &table, "rb_user").await.unwrap();
+            assert_eq!(report.added.len(), 0);
+            assert_eq!(report.skipped, vec!["id", "age"]);
+        };
+        block_on(f);
+        assert!(is_same_type("INT8", "bigint"));
+        assert!(is_same_type("VARCHAR(50) NOT NULL", "character varying(50)"));
+        assert!(is_same_type("DECIMAL", "decimal(10,2)"));
+        assert!(is_same_type("int", "int(11)"));
+        assert!(!is_same_type("VARCHAR(50)", "varchar(20)"));
+        assert!(!is_same_type("TEXT", "INTEGER"));
+    }
+
+    #[test]
+    fn test_table_sync_table_def() {
+        use rbatis::table_sync::{ForeignKeyDef, SqliteTableMapper, TableDef};
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            let mapper = &SqliteTableMapper {};
+            conn.exec("create table author (id integer primary key, name text)", vec![])
+                .await
+                .unwrap();
+            let def = TableDef::new("post")
+                .primary_key(&["id", "version"])
+                .not_null("title")
+                .default_value("status", "0")
+                .unique("uk_post_title", &["author_id", "title"])
+                .foreign_key(
+                    ForeignKeyDef::new("fk_post_author", &["author_id"], "author", &["id"])
+                        .on_delete("CASCADE"),
+                );
+            let table = rbs::value! {"id": "INTEGER", "version": "INTEGER", "author_id": "INTEGER", "title": "TEXT", "status": "INTEGER"};
+            let report = def.sync(&conn, mapper, table.clone()).await.unwrap();
+            assert_eq!(
+                report.ddl,
+                vec![
+                    "CREATE TABLE post (id INTEGER,version INTEGER,author_id INTEGER,title TEXT NOT NULL,status INTEGER DEFAULT 0,PRIMARY KEY (id,version),CONSTRAINT fk_post_author FOREIGN KEY (author_id) REFERENCES author (id) ON DELETE CASCADE);",
+                    "CREATE UNIQUE INDEX uk_post_title ON post (author_id,title);",
+                ]
+            );
+            let post = schema::table_info(&conn, "post").await.unwrap().unwrap();
+            assert_eq!(post.primary_keys, vec!["id", "version"]);
+            assert!(!post.column("title").unwrap().nullable);
+            assert_eq!(post.column("status").unwrap().default, Some("0".to_string()));
+            assert_eq!(post.foreign_keys[0].ref_table, "author");
+            //idempotent
+            let report = def.sync(&conn, mapper, table.clone()).await.unwrap();
+            assert_eq!(report.ddl, Vec::<String>::new());
+            assert_eq!(report.warnings, Vec::<String>::new());
+            //the NOT NULL column not declared
+            let report = TableDef::new("post")
+                .primary_key(&["id", "version"])
+                .plan(&conn, mapper, table.clone())
+                .await
+                .unwrap();
+            assert_eq!(
+                report.warnings,
+                vec!["column 'title' is NOT NULL, but not declared NOT NULL"]
+            );
+
+            //add an index and a foreign key to the existing table
+            let def = def
+                .index("idx_post_status", &["status"])
+                .foreign_key(ForeignKeyDef::new("fk_post_parent", &["id"], "post", &["id"]));
+            let report = def.sync(&conn, mapper, table).await.unwrap();
+            assert_eq!(report.ddl, vec!["CREATE INDEX idx_post_status ON post (status);"]);
+            assert_eq!(
+                report.warnings,
+                vec!["sqlite can't add the foreign key 'fk_post_parent' to an existing table"]
+            );
+            assert_eq!(schema::indexes(&conn, "post").await.unwrap().len(), 2);
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_override_mapper() {
+        use rbatis::table_sync::{ColumnMapper, MysqlTableMapper, OverrideMapper};
+        use rbs::Value;
+        let mapper = OverrideMapper::new(MysqlTableMapper {})
+            .ext("Decimal", "DECIMAL(18,4)")
+            .ext("Uuid", "BINARY(16)")
+            .value("Bool", "BOOLEAN")
+            .value("String", "VARCHAR(255)")
+            .column("remark", "TEXT");
+        let decimal = Value::Ext("Decimal", Box::new(Value::String("1.5".to_string())));
+        let uuid = Value::Ext("Uuid", Box::new(Value::String("".to_string())));
+        let date = Value::Ext("Date", Box::new(Value::String("".to_string())));
+        assert_eq!(mapper.driver_type(), "mysql");
+        assert_eq!(mapper.get_column_type("price", &decimal), "DECIMAL(18,4)");
+        assert_eq!(mapper.get_column_type("id", &uuid), "BINARY(16)");
+        assert_eq!(mapper.get_column_type("enable", &Value::Bool(true)), "BOOLEAN");
+        assert_eq!(mapper.get_column_type("name", &Value::String("".to_string())), "VARCHAR(255)");
+        assert_eq!(mapper.get_column_type("name", &Value::String("CHAR(2)".to_string())), "CHAR(2)");
+        assert_eq!(mapper.get_column_type("remark", &Value::String("CHAR(2)".to_string())), "TEXT");
+        //the type of the inner mapper by the column name is kept
+        assert_eq!(mapper.get_column_type("id", &Value::String("".to_string())), "VARCHAR(50)");
+        assert_eq!(mapper.get_column_type("user_id", &Value::String("".to_string())), "VARCHAR(50)");
+        //not overridden
+        assert_eq!(mapper.get_column_type("day", &date), "DATE");
+        assert_eq!(mapper.get_column_type("age", &Value::I32(1)), "INT");
+    }
+
+    #[test]
+    fn test_table_sync_export() {
+        use rbatis::migration::split_sql;
+        use rbatis::table_sync::{export, export_schema, ForeignKeyDef, SqliteTableMapper, TableDef};
+        let mapper = &SqliteTableMapper {};
+        let post = TableDef::new("post")
+            .primary_key(&["id"])
+            .not_null("title")
+            .index("idx_post_title", &["title"])
+            .foreign_key(ForeignKeyDef::new("fk_post_author", &["author_id"], "author", &["id"]));
+        let author = TableDef::new("author").primary_key(&["id"]);
+        let script = export(
+            mapper,
+            &[
+                (post, rbs::value! {"id": "INTEGER", "author_id": "INTEGER", "title": "TEXT"}),
+                (author, rbs::value! {"id": "INTEGER", "name": "TEXT"}),
+            ],
+        )
+        .unwrap();
+        assert_eq!(
+            script,
+            "-- sqlite schema, generated by rbatis
+
+CREATE TABLE author (id INTEGER,name TEXT,PRIMARY KEY (id));
+
+CREATE TABLE post (id INTEGER,author_id INTEGER,title TEXT NOT NULL,PRIMARY KEY (id),CONSTRAINT fk_post_author FOREIGN KEY (author_id) REFERENCES author (id));
+CREATE INDEX idx_post_title ON post (title);
+"
+        );
+        let f = async move {
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, "sqlite://:memory:").unwrap();
+            let conn = rb.acquire().await.unwrap();
+            for sql in split_sql(&script) {
+                conn.exec(&sql, vec![]).await.unwrap();
+            }
+            conn.exec("create table tag (id integer, name text unique, post_id integer references post(id) on delete cascade, status integer default 1)", vec![])
+                .await
+                .unwrap();
+            //from introspection, the foreign key names of sqlite are lost
+            let exported = export_schema(&conn, mapper, &[]).await.unwrap();
+            assert_eq!(
+                exported,
+                script.replace("fk_post_author ", "fk_post_author_id ")
+                    + "
+CREATE TABLE tag (id INTEGER,name TEXT,post_id INTEGER,status INTEGER DEFAULT 1,CONSTRAINT fk_tag_post_id FOREIGN KEY (post_id) REFERENCES post (id) ON DELETE CASCADE);
+CREATE UNIQUE INDEX uk_tag_name ON tag (name);
+"
+            );
+        };
+        block_on(f);
+    }
+
+    #[test]
+    fn test_table_sync_export_cycle() {
+        use rbatis::table_sync::{export, ForeignKeyDef, MysqlTableMapper, SqliteTableMapper, TableDef};
+        let tables = [
+            (
+                TableDef::new("dept").foreign_key(ForeignKeyDef::new("fk_dept_manager", &["manager_id"], "emp", &["id"])),
+                rbs::value! {"id": "INT", "manager_id": "INT"},
+            ),
+            (
+                TableDef::new("emp").foreign_key(ForeignKeyDef::new("fk_emp_dept", &["dept_id"], "dept", &["id"])),
+                rbs::value! {"id": "INT", "dept_id": "INT"},
+            ),
+            (
+                TableDef::new("badge").foreign_key(ForeignKeyDef::new("fk_badge_emp", &["emp_id"], "emp", &["id"])),
+                rbs::value! {"id": "INT", "emp_id": "INT"},
+            ),
+        ];
+        let script = export(&MysqlTableMapper {}, &tables).unwrap();
+        assert_eq!(
+            script,
+            "-- mysql schema, generated by rbatis
+
+CREATE TABLE dept (id INT,manager_id INT);
+
+CREATE TABLE emp (id INT,dept_id INT,CONSTRAINT fk_emp_dept FOREIGN KEY (dept_id) REFERENCES dept (id));
+
+CREATE TABLE badge (id INT,emp_id INT,CONSTRAINT fk_badge_emp FOREIGN KEY (emp_id) REFERENCES emp (id));
+
+ALTER TABLE dept ADD CONSTRAINT fk_dept_manager FOREIGN KEY (manager_id) REFERENCES emp (id);
+"
+        );
+        //sqlite creates the foreign key to a table not created yet
+        let script = export(&SqliteTableMapper {}, &tables).unwrap();
+        assert!(script.contains("CREATE TABLE dept (id INT,manager_id INT,CONSTRAINT fk_dept_manager"));
+        assert!(!script.contains("ALTER TABLE"));
+    }
+}
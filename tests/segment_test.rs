@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod test {
+    use rbatis::segment::{reserve, Segment, SegmentRange};
+    use rbatis::RBatis;
+    use rbdc::rt::block_on;
+    use rbdc_sqlite::driver::SqliteDriver;
+
+    #[test]
+    fn test_segment() {
+        let f = async move {
+            let path = std::env::temp_dir().join("rbatis_segment_test.db");
+            let _ = std::fs::remove_file(&path);
+            let url = format!("sqlite://{}", path.display());
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, &url).unwrap();
+            let segment = Segment::new(&rb, "order", 10);
+            let mut ids = vec![];
+            for _ in 0..25 {
+                ids.push(segment.next_id().await.unwrap());
+            }
+            assert_eq!(ids[0], 1);
+            for pair in ids.windows(2) {
+                assert!(pair[1] > pair[0], "{:?}", ids);
+            }
+            //another tag
+            let user = Segment::new(&rb, "user", 100);
+            assert_eq!(user.next_id().await.unwrap(), 1);
+
+            //restart, the ids are reserved after the last range
+            let last = *ids.last().unwrap();
+            let rb = RBatis::new();
+            rb.init(SqliteDriver {}, &url).unwrap();
+            let segment = Segment::new(&rb, "order", 10);
+            let id = segment.next_id().await.unwrap();
+            assert!(id > last, "{} > {}", id, last);
+
+            //concurrent, the ids are unique
+            let mut tasks = vec![];
+            for _ in 0..4 {
+                let segment = segment.clone();
+                tasks.push(rbdc::rt::spawn(async move {
+                    let mut ids = vec![];
+                    for _ in 0..50 {
+                        ids.push(segment.next_id().await.unwrap());
+                    }
+                    ids
+                }));
+            }
+            let mut all = vec![];
+            for task in tasks {
+                all.extend(task.await.unwrap());
+            }
+            all.sort();
+            all.dedup();
+            assert_eq!(all.len(), 200);
+
+            let range = reserve(&rb, "order", 10).await.unwrap();
+            let next = reserve(&rb, "order", 10).await.unwrap();
+            assert_eq!(
+                next,
+                SegmentRange {
+                    id: range.max,
+                    max: range.max + 10,
+                    step: 10,
+                }
+            );
+            let _ = std::fs::remove_file(&path);
+        };
+        block_on(f);
+    }
+}